@@ -2,23 +2,119 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
 use std::{
-    fmt, ops,
-    sync::{Arc, Weak},
+    fmt,
+    future::{poll_fn, Future},
+    mem::ManuallyDrop,
+    ops,
+    panic::Location,
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    task::{Context, Poll},
+    time::Duration,
 };
-use tokio::sync::{OwnedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tokio::sync::{Notify, OwnedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 #[cfg(feature = "lite")]
 pub mod lite;
+pub mod once;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod track;
+mod trait_impls;
+
+pub use readlock::traits;
+
+use readlock::__private::{CoerceUnsized, ReaderCount};
+
+use self::track::{Acquisition, LockKind, LockState};
+
+/// The bookkeeping state of a value, shared by its `Shared`, `SharedReadLock`s
+/// and `WeakReadLock`s.
+///
+/// This lives in its own allocation next to the `Arc<RwLock<T>>`, so that
+/// stays the internal representation of `Shared` and `SharedReadLock`.
+struct Meta {
+    state: LockState,
+    readers: ReaderCount,
+    writer_alive: AtomicBool,
+    writer_dropped: Notify,
+}
+
+impl Meta {
+    fn new(state: LockState) -> Self {
+        Self {
+            state,
+            readers: ReaderCount::new(),
+            writer_alive: AtomicBool::new(true),
+            writer_dropped: Notify::new(),
+        }
+    }
+
+    fn fmt_value<T: fmt::Debug + ?Sized>(
+        &self,
+        rwlock: &RwLock<T>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        if let Some(name) = self.state.name() {
+            write!(f, "{name}: ")?;
+        }
+        fmt::Debug::fmt(rwlock, f)
+    }
+}
+
+/// Releases a reader of [`Meta::readers`] when dropped.
+///
+/// Held by [`OwnedSharedReadGuard`], which keeps counting as a reader after
+/// consuming its `SharedReadLock`.
+struct Reader(Arc<Meta>);
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        self.0.readers.release();
+    }
+}
 
 /// A wrapper around a resource possibly shared with [`SharedReadLock`]s and
 /// [`WeakReadLock`]s, but no other `Shared`s.
-pub struct Shared<T: ?Sized>(Arc<RwLock<T>>);
+pub struct Shared<T: ?Sized>(Arc<RwLock<T>>, Arc<Meta>);
 
 #[allow(clippy::arc_with_non_send_sync)] // should not fire for generics
 impl<T> Shared<T> {
     /// Create a new `Shared`.
     pub fn new(data: T) -> Self {
-        Self(Arc::new(RwLock::new(data)))
+        Self(Arc::new(RwLock::new(data)), Arc::new(Meta::new(LockState::new(None))))
+    }
+
+    /// Create a new `Shared` with a name.
+    ///
+    /// The name is included in the `Debug` output of the `Shared` and its
+    /// associated `SharedReadLock`s, as well as in debugging output such as
+    /// the spans emitted with the `tracing` feature.
+    ///
+    /// Only available with the `tracing` or `hold-warnings` features, since
+    /// the name is not stored otherwise.
+    #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+    pub fn with_name(data: T, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        let meta = Meta::new(LockState::new(Some(name.into())));
+        Self(Arc::new(RwLock::new(data)), Arc::new(meta))
+    }
+
+    /// Create a new `Shared` whose inner value can hold a [`WeakReadLock`] to
+    /// itself.
+    ///
+    /// `data_fn` is called with a `WeakReadLock` for the value that is being
+    /// created, which can be cloned and stored inside of it. Upgrading it
+    /// returns `None` until `new_cyclic` has returned.
+    pub fn new_cyclic(data_fn: impl FnOnce(&WeakReadLock<T>) -> T) -> Self {
+        let meta = Arc::new(Meta::new(LockState::new(None)));
+        let rwlock = Arc::new_cyclic(|weak| {
+            let weak = WeakReadLock(weak.clone(), Arc::downgrade(&meta));
+            RwLock::new(data_fn(&weak))
+        });
+        Self(rwlock, meta)
     }
 
     /// Returns the inner value, if the `Shared` has no associated
@@ -28,14 +124,56 @@ impl<T> Shared<T> {
     /// in.
     ///
     /// This will succeed even if there are outstanding weak references.
+    pub fn unwrap(this: Self) -> Result<T, Self> {
+        let (rwlock, meta) = Self::into_parts(this);
+        match Arc::try_unwrap(rwlock) {
+            Ok(rwlock) => Ok(rwlock.into_inner()),
+            Err(rwlock) => Err(Self(rwlock, meta)),
+        }
+    }
+
+    /// Returns the inner value once all associated `SharedReadLock`s have been
+    /// dropped, causing the current task to yield until then.
+    ///
+    /// `WeakReadLock`s can still be upgraded while waiting, in which case the
+    /// resulting `SharedReadLock`s have to be dropped as well.
+    pub async fn unwrap_when_unique(this: Self) -> T {
+        let mut this = Some(this);
+        poll_fn(|cx| Self::poll_unique(&mut this, cx)).await
+    }
+
+    /// Like [`unwrap_when_unique`][Self::unwrap_when_unique], but gives up
+    /// after `timeout`, returning the `Shared` that was passed in.
     ///
     /// # Panics
     ///
-    /// This function will panic if the lock around the inner value is poisoned.
-    pub fn unwrap(this: Self) -> Result<T, Self> {
-        match Arc::try_unwrap(this.0) {
-            Ok(rwlock) => Ok(rwlock.into_inner()),
-            Err(arc) => Err(Self(arc)),
+    /// This function panics if it is not called from within a Tokio runtime
+    /// with the time driver enabled.
+    pub async fn unwrap_when_unique_timeout(this: Self, timeout: Duration) -> Result<T, Self> {
+        let mut this = Some(this);
+        match tokio::time::timeout(timeout, poll_fn(|cx| Self::poll_unique(&mut this, cx))).await {
+            Ok(data) => Ok(data),
+            Err(_) => {
+                let this = this.expect("the `Shared` is only taken on success");
+                this.1.readers.unregister();
+                Err(this)
+            }
+        }
+    }
+
+    fn poll_unique(this: &mut Option<Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let shared = this.take().expect("polled after completion");
+        shared.1.readers.register(cx.waker());
+        match Self::unwrap(shared) {
+            Ok(data) => Poll::Ready(data),
+            Err(shared) => {
+                if shared.1.readers.is_zero() {
+                    // A `SharedReadLock` is being created or dropped right now.
+                    cx.waker().wake_by_ref();
+                }
+                *this = Some(shared);
+                Poll::Pending
+            }
         }
     }
 }
@@ -55,14 +193,33 @@ impl<T: ?Sized> Shared<T> {
 
     /// Lock this `Shared` to be able to mutate it, causing the current task to
     /// yield until the lock has been acquired.
-    pub async fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
-        SharedWriteGuard(this.0.write().await)
+    #[track_caller]
+    pub fn lock(this: &mut Self) -> impl Future<Output = SharedWriteGuard<'_, T>> {
+        let location = Location::caller();
+        async move {
+            let mut acquisition =
+                Acquisition::start(&this.1.state, LockKind::Write, true, location);
+            let guard = acquisition.wait(this.0.write()).await;
+            acquisition.acquired();
+            SharedWriteGuard(guard, acquisition)
+        }
     }
 
     /// Get a [`SharedReadLock`] for accessing the same resource read-only from
     /// elsewhere.
     pub fn get_read_lock(this: &Self) -> SharedReadLock<T> {
-        SharedReadLock(this.0.clone())
+        this.1.readers.acquire();
+        SharedReadLock(this.0.clone(), this.1.clone())
+    }
+
+    /// Set a function that is called whenever the number of associated
+    /// [`SharedReadLock`]s drops to zero, replacing any previous one.
+    ///
+    /// The function is called on the thread that drops the last
+    /// `SharedReadLock`, before that `SharedReadLock` has released its
+    /// reference to the inner value.
+    pub fn on_unique(this: &Self, hook: impl Fn() + Send + Sync + 'static) {
+        this.1.readers.set_hook(hook);
     }
 
     /// Attempt to create a `Shared` from its internal representation,
@@ -74,15 +231,19 @@ impl<T: ?Sized> Shared<T> {
     /// would be broken.
     pub fn try_from_inner(rwlock: Arc<RwLock<T>>) -> Result<Self, Arc<RwLock<T>>> {
         if Arc::strong_count(&rwlock) == 1 && Arc::weak_count(&rwlock) == 0 {
-            Ok(Self(rwlock))
+            Ok(Self(rwlock, Arc::new(Meta::new(LockState::new(None)))))
         } else {
             Err(rwlock)
         }
     }
 
     /// Turns this `Shared` into its internal representation, `Arc<RwLock<T>>`.
+    ///
+    /// Since the `Arc<RwLock<T>>` can still be used to mutate the value, the
+    /// associated [`SharedReadLock`]s keep treating the `Shared` as
+    /// [alive][SharedReadLock::is_writer_alive].
     pub fn into_inner(this: Self) -> Arc<RwLock<T>> {
-        this.0
+        Self::into_parts(this).0
     }
 
     /// Gets the number of associated [`SharedReadLock`]s.
@@ -94,6 +255,12 @@ impl<T: ?Sized> Shared<T> {
     pub fn weak_count(this: &Self) -> usize {
         Arc::weak_count(&this.0)
     }
+
+    fn into_parts(this: Self) -> (Arc<RwLock<T>>, Arc<Meta>) {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { (ptr::read(&this.0), ptr::read(&this.1)) }
+    }
 }
 
 /// SAFETY: Only allowed for a read guard obtained from the inner value of a
@@ -117,7 +284,7 @@ impl<T: ?Sized> ops::Deref for Shared<T> {
 
 impl<T: fmt::Debug + ?Sized> fmt::Debug for Shared<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.1.fmt_value(&self.0, f)
     }
 }
 
@@ -127,23 +294,47 @@ impl<T: Default> Default for Shared<T> {
     }
 }
 
+impl<T: ?Sized> Drop for Shared<T> {
+    fn drop(&mut self) {
+        self.1.writer_alive.store(false, Ordering::Release);
+        self.1.writer_dropped.notify_waiters();
+    }
+}
+
 /// A read-only reference to a resource possibly shared with up to one
 /// [`Shared`] and many [`WeakReadLock`]s.
-pub struct SharedReadLock<T: ?Sized>(Arc<RwLock<T>>);
+pub struct SharedReadLock<T: ?Sized>(Arc<RwLock<T>>, Arc<Meta>);
 
 impl<T: ?Sized> SharedReadLock<T> {
     /// Lock this `SharedReadLock`, causing the current task to yield until the
     /// lock has been acquired.
-    pub async fn lock(&self) -> SharedReadGuard<'_, T> {
-        SharedReadGuard(self.0.read().await)
+    #[track_caller]
+    pub fn lock(&self) -> impl Future<Output = SharedReadGuard<'_, T>> {
+        self.lock_at(Location::caller())
+    }
+
+    /// Lock this `SharedReadLock` on behalf of a caller at `location`.
+    pub(crate) async fn lock_at(
+        &self,
+        location: &'static Location<'static>,
+    ) -> SharedReadGuard<'_, T> {
+        let mut acquisition = Acquisition::start(&self.1.state, LockKind::Read, true, location);
+        let guard = acquisition.wait(self.0.read()).await;
+        acquisition.acquired();
+        SharedReadGuard(guard, acquisition)
     }
 
     /// Try to lock this `SharedReadLock`.
     ///
     /// If the value is currently locked for writing through the corresponding
     /// `Shared` instance, returns [`TryLockError`].
+    #[track_caller]
     pub fn try_lock(&self) -> TryLockResult<SharedReadGuard<'_, T>> {
-        self.0.try_read().map(SharedReadGuard).map_err(TryLockError)
+        let mut acquisition =
+            Acquisition::start(&self.1.state, LockKind::Read, false, Location::caller());
+        let guard = self.0.try_read().map_err(TryLockError)?;
+        acquisition.acquired();
+        Ok(SharedReadGuard(guard, acquisition))
     }
 
     /// Lock this `SharedReadLock`, causing the current task to yield until the
@@ -152,13 +343,45 @@ impl<T: ?Sized> SharedReadLock<T> {
     /// This method is identical to [`lock`][Self::lock], except that the
     /// returned guard keeps a clone of the internal [`Arc`] instead of
     /// borrowing it. Therefore, the guard does has a `'static` lifetime.
-    pub async fn lock_owned(self) -> OwnedSharedReadGuard<T> {
-        OwnedSharedReadGuard(self.0.read_owned().await)
+    #[track_caller]
+    pub fn lock_owned(self) -> impl Future<Output = OwnedSharedReadGuard<T>> {
+        let location = Location::caller();
+        async move {
+            let (rwlock, meta) = self.into_parts();
+            let reader = Reader(meta);
+            let mut acquisition =
+                Acquisition::start(&reader.0.state, LockKind::Read, true, location);
+            let guard = acquisition.wait(rwlock.read_owned()).await;
+            acquisition.acquired();
+            OwnedSharedReadGuard(guard, acquisition, Some(reader))
+        }
+    }
+
+    /// Returns `true` if the [`Shared`] for this value has not been dropped
+    /// yet, i.e. the value may still change.
+    ///
+    /// Once this returns `false`, it only returns `true` again if a
+    /// `SharedReadLock` for the value is [upgraded][Self::try_upgrade].
+    pub fn is_writer_alive(&self) -> bool {
+        self.1.writer_alive.load(Ordering::Acquire)
+    }
+
+    /// Wait until the [`Shared`] for this value has been dropped.
+    ///
+    /// Completes immediately if it was dropped already.
+    pub async fn writer_dropped(&self) {
+        // Created before checking the flag, so the notification can't be
+        // missed: `notify_waiters` also wakes `Notified`s that haven't been
+        // polled yet.
+        let dropped = self.1.writer_dropped.notified();
+        if self.is_writer_alive() {
+            dropped.await;
+        }
     }
 
     /// Create a new [`WeakReadLock`] pointer to this allocation.
     pub fn downgrade(&self) -> WeakReadLock<T> {
-        WeakReadLock(Arc::downgrade(&self.0))
+        WeakReadLock(Arc::downgrade(&self.0), Arc::downgrade(&self.1))
     }
 
     /// Upgrade a `SharedReadLock` to `Shared`.
@@ -170,7 +393,12 @@ impl<T: ?Sized> SharedReadLock<T> {
     /// being the only reference that is able to mutate the inner value.
     pub fn try_upgrade(self) -> Result<Shared<T>, Self> {
         if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
-            Ok(Shared(self.0))
+            let (rwlock, meta) = self.into_parts();
+            // The value is not unique in the sense of `on_unique`, since it is
+            // owned by the new `Shared`.
+            meta.readers.release_silently();
+            meta.writer_alive.store(true, Ordering::Release);
+            Ok(Shared(rwlock, meta))
         } else {
             Err(self)
         }
@@ -184,8 +412,13 @@ impl<T: ?Sized> SharedReadLock<T> {
     /// a value that can be written only from inside one module or crate, but
     /// outside users should be allowed to obtain a reusable lock for reading
     /// the inner value.
+    ///
+    /// Since there is no [`Shared`], the writer is always considered
+    /// [alive][Self::is_writer_alive].
     pub fn from_inner(rwlock: Arc<RwLock<T>>) -> Self {
-        Self(rwlock)
+        let meta = Arc::new(Meta::new(LockState::new(None)));
+        meta.readers.acquire();
+        Self(rwlock, meta)
     }
 
     /// Attempt to turn this `SharedReadLock` into its internal representation,
@@ -199,28 +432,45 @@ impl<T: ?Sized> SharedReadLock<T> {
     /// mutate the inner value.
     pub fn try_into_inner(self) -> Result<Arc<RwLock<T>>, Self> {
         if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
-            Ok(self.0)
+            let (rwlock, meta) = self.into_parts();
+            meta.readers.release();
+            Ok(rwlock)
         } else {
             Err(self)
         }
     }
+
+    /// Split this `SharedReadLock` into its parts, without releasing it as a
+    /// reader.
+    fn into_parts(self) -> (Arc<RwLock<T>>, Arc<Meta>) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { (ptr::read(&this.0), ptr::read(&this.1)) }
+    }
 }
 
 impl<T: ?Sized> Clone for SharedReadLock<T> {
     fn clone(&self) -> Self {
-        Self(Arc::clone(&self.0))
+        self.1.readers.acquire();
+        Self(Arc::clone(&self.0), Arc::clone(&self.1))
+    }
+}
+
+impl<T: ?Sized> Drop for SharedReadLock<T> {
+    fn drop(&mut self) {
+        self.1.readers.release();
     }
 }
 
 impl<T: fmt::Debug + ?Sized> fmt::Debug for SharedReadLock<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.1.fmt_value(&self.0, f)
     }
 }
 
 /// A weak read-only reference to a resource possibly shared with up to one
 /// [`Shared`], and many [`SharedReadLock`]s.
-pub struct WeakReadLock<T: ?Sized>(Weak<RwLock<T>>);
+pub struct WeakReadLock<T: ?Sized>(Weak<RwLock<T>>, Weak<Meta>);
 
 impl<T: ?Sized> WeakReadLock<T> {
     /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`, delaying
@@ -228,13 +478,16 @@ impl<T: ?Sized> WeakReadLock<T> {
     ///
     /// Returns `None` if the inner value has already been dropped.
     pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
-        Weak::upgrade(&self.0).map(SharedReadLock)
+        let meta = Weak::upgrade(&self.1)?;
+        let rwlock = Weak::upgrade(&self.0)?;
+        meta.readers.acquire();
+        Some(SharedReadLock(rwlock, meta))
     }
 }
 
 impl<T: ?Sized> Clone for WeakReadLock<T> {
     fn clone(&self) -> Self {
-        Self(Weak::clone(&self.0))
+        Self(Weak::clone(&self.0), Weak::clone(&self.1))
     }
 }
 
@@ -244,16 +497,77 @@ impl<T: fmt::Debug + ?Sized> fmt::Debug for WeakReadLock<T> {
     }
 }
 
+impl<T: ?Sized> CoerceUnsized<T> for Shared<T> {
+    type Output<U: ?Sized> = Shared<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(self, f: impl FnOnce(*const T) -> *const U) -> Shared<U> {
+        let (rwlock, meta) = Shared::into_parts(self);
+        // SAFETY: Upheld by the caller.
+        Shared(unsafe { coerce_arc(rwlock, f) }, meta)
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for SharedReadLock<T> {
+    type Output<U: ?Sized> = SharedReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> SharedReadLock<U> {
+        let (rwlock, meta) = self.into_parts();
+        // SAFETY: Upheld by the caller.
+        SharedReadLock(unsafe { coerce_arc(rwlock, f) }, meta)
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for WeakReadLock<T> {
+    type Output<U: ?Sized> = WeakReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> WeakReadLock<U> {
+        let ptr = Weak::into_raw(self.0);
+        // SAFETY: Upheld by the caller, see `coerce_arc`.
+        let rwlock = unsafe { Weak::from_raw(f(ptr as *const T) as *const RwLock<U>) };
+        WeakReadLock(rwlock, self.1)
+    }
+}
+
+/// # Safety
+///
+/// See [`CoerceUnsized::__coerce_unsized`].
+unsafe fn coerce_arc<T: ?Sized, U: ?Sized>(
+    rwlock: Arc<RwLock<T>>,
+    f: impl FnOnce(*const T) -> *const U,
+) -> Arc<RwLock<U>> {
+    // The pointer metadata of `RwLock<T>` is the same as that of `T`, so these
+    // casts only change the pointee type. Together with `f`, this is the same
+    // as coercing `Arc<RwLock<T>>` to `Arc<RwLock<U>>`.
+    let ptr = Arc::into_raw(rwlock);
+    unsafe { Arc::from_raw(f(ptr as *const T) as *const RwLock<U>) }
+}
+
+impl<T, const N: usize> From<[T; N]> for Shared<[T]> {
+    fn from(array: [T; N]) -> Self {
+        readlock::coerce!(Shared::new(array) => [T])
+    }
+}
+
 /// RAII structure used to release the shared read access of a lock when
 /// dropped.
 #[clippy::has_significant_drop]
-pub struct SharedReadGuard<'a, T: ?Sized>(RwLockReadGuard<'a, T>);
+pub struct SharedReadGuard<'a, T: ?Sized>(
+    RwLockReadGuard<'a, T>,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Acquisition,
+);
 
 impl<'a, T: ?Sized + 'a> SharedReadGuard<'a, T> {
     /// Create a `SharedReadGuard` from its internal representation,
     /// `RwLockReadGuard<'a, T>`.
     pub fn from_inner(guard: RwLockReadGuard<'a, T>) -> Self {
-        Self(guard)
+        Self(guard, Acquisition::untracked())
     }
 }
 
@@ -273,14 +587,22 @@ impl<'a, T: fmt::Debug + ?Sized + 'a> fmt::Debug for SharedReadGuard<'a, T> {
 
 /// RAII structure used to release the shared read access of a lock when
 /// dropped.
+///
+/// Returned by [`SharedReadLock::lock_owned`].
 #[clippy::has_significant_drop]
-pub struct OwnedSharedReadGuard<T: ?Sized>(OwnedRwLockReadGuard<T>);
+pub struct OwnedSharedReadGuard<T: ?Sized>(
+    OwnedRwLockReadGuard<T>,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Acquisition,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Option<Reader>,
+);
 
 impl<T: ?Sized> OwnedSharedReadGuard<T> {
     /// Create a `SharedReadGuard` from its internal representation,
     /// `OwnedRwLockReadGuard< T>`.
     pub fn from_inner(guard: OwnedRwLockReadGuard<T>) -> Self {
-        Self(guard)
+        Self(guard, Acquisition::untracked(), None)
     }
 }
 
@@ -301,7 +623,11 @@ impl<T: fmt::Debug + ?Sized> fmt::Debug for OwnedSharedReadGuard<T> {
 /// RAII structure used to release the exclusive write access of a lock when
 /// dropped.
 #[clippy::has_significant_drop]
-pub struct SharedWriteGuard<'a, T: ?Sized>(RwLockWriteGuard<'a, T>);
+pub struct SharedWriteGuard<'a, T: ?Sized>(
+    RwLockWriteGuard<'a, T>,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Acquisition,
+);
 
 impl<'a, T: ?Sized + 'a> ops::Deref for SharedWriteGuard<'a, T> {
     type Target = T;
@@ -315,7 +641,7 @@ impl<'a, T: ?Sized> SharedWriteGuard<'a, T> {
     /// Create a `SharedWriteGuard` from its internal representation,
     /// `RwLockWriteGuard<'a, T>`.
     pub fn from_inner(guard: RwLockWriteGuard<'a, T>) -> Self {
-        Self(guard)
+        Self(guard, Acquisition::untracked())
     }
 }
 
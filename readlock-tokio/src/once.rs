@@ -0,0 +1,226 @@
+//! A [`Shared`] that hands out read locks before its value exists.
+//!
+//! [`OnceShared`] is created without a value. Readers obtained through
+//! [`OnceShared::get_read_lock`] wait in [`SharedReadLock::lock`] until the
+//! value has been set through [`OnceShared::init`], which turns the
+//! `OnceShared` into a regular [`Shared`].
+//!
+//! Once initialized, all `SharedReadLock`s of this module together hold a
+//! single [`crate::SharedReadLock`] for the value, so they count as one reader
+//! in [`Shared::read_count`] and [`Shared::unwrap`] only succeeds after all of
+//! them have been dropped. That `crate::SharedReadLock` can be accessed
+//! through [`SharedReadLock::get`] to use the rest of its API, for example to
+//! get a [`crate::WeakReadLock`] for the value.
+
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, Weak,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{Shared, SharedReadGuard};
+
+/// The state shared between a `OnceShared` and its readers.
+struct Slot<T> {
+    read_lock: OnceLock<crate::SharedReadLock<T>>,
+    /// Set once the value has been initialized, or the `OnceShared` has been
+    /// dropped without initializing it.
+    done: AtomicBool,
+    ready: Notify,
+}
+
+impl<T> Slot<T> {
+    async fn wait(&self) -> &crate::SharedReadLock<T> {
+        // Created before checking the flag, so the notification can't be
+        // missed: `notify_waiters` also wakes `Notified`s that haven't been
+        // polled yet.
+        let ready = self.ready.notified();
+        if !self.done.load(Ordering::Acquire) {
+            ready.await;
+        }
+        self.read_lock.get().expect("`OnceShared` was dropped without being initialized")
+    }
+}
+
+/// The writer of a value that has not been initialized yet.
+pub struct OnceShared<T>(Arc<Slot<T>>);
+
+impl<T> OnceShared<T> {
+    /// Create a new `OnceShared`.
+    pub fn new() -> Self {
+        Self(Arc::new(Slot {
+            read_lock: OnceLock::new(),
+            done: AtomicBool::new(false),
+            ready: Notify::new(),
+        }))
+    }
+
+    /// Get a [`SharedReadLock`] for reading the value once it has been
+    /// initialized.
+    pub fn get_read_lock(&self) -> SharedReadLock<T> {
+        SharedReadLock(self.0.clone())
+    }
+
+    /// Initialize the value, waking up all readers that are waiting for it.
+    ///
+    /// Returns the [`Shared`] that is used to mutate the value from now on.
+    pub fn init(self, value: T) -> Shared<T> {
+        let shared = Shared::new(value);
+        let read_lock = Shared::get_read_lock(&shared);
+        if self.0.read_lock.set(read_lock).is_err() {
+            unreachable!("`OnceShared` is only initialized once");
+        }
+        // Readers are woken up when `self` is dropped.
+        shared
+    }
+}
+
+impl<T> Default for OnceShared<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OnceShared<T> {
+    fn drop(&mut self) {
+        self.0.done.store(true, Ordering::Release);
+        self.0.ready.notify_waiters();
+    }
+}
+
+impl<T> fmt::Debug for OnceShared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceShared").finish_non_exhaustive()
+    }
+}
+
+/// A read-only reference to a value that may not have been initialized yet.
+pub struct SharedReadLock<T>(Arc<Slot<T>>);
+
+impl<T> SharedReadLock<T> {
+    /// Lock this `SharedReadLock`, causing the current task to yield until the
+    /// value has been initialized and the lock has been acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`OnceShared`] was dropped without
+    /// initializing the value.
+    #[track_caller]
+    pub fn lock(&self) -> impl Future<Output = SharedReadGuard<'_, T>> {
+        let location = Location::caller();
+        async move { self.0.wait().await.lock_at(location).await }
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// If the value has not been initialized yet, returns
+    /// [`TryLockError::Uninitialized`]. This is also the case if the
+    /// [`OnceShared`] was dropped without initializing it.
+    #[track_caller]
+    pub fn try_lock(&self) -> Result<SharedReadGuard<'_, T>, TryLockError> {
+        match self.0.read_lock.get() {
+            Some(read_lock) => read_lock.try_lock().map_err(TryLockError::Locked),
+            None => Err(TryLockError::Uninitialized),
+        }
+    }
+
+    /// Returns `true` if the value has been initialized.
+    pub fn is_initialized(&self) -> bool {
+        self.0.read_lock.get().is_some()
+    }
+
+    /// Get the [`crate::SharedReadLock`] for the value, if it has been
+    /// initialized.
+    pub fn get(&self) -> Option<&crate::SharedReadLock<T>> {
+        self.0.read_lock.get()
+    }
+
+    /// Get the [`crate::SharedReadLock`] for the value, waiting until it has
+    /// been initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`OnceShared`] was dropped without
+    /// initializing the value.
+    pub async fn wait(&self) -> &crate::SharedReadLock<T> {
+        self.0.wait().await
+    }
+
+    /// Create a new [`WeakReadLock`] pointer to this value.
+    pub fn downgrade(&self) -> WeakReadLock<T> {
+        WeakReadLock(Arc::downgrade(&self.0))
+    }
+}
+
+impl<T> Clone for SharedReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(read_lock) => read_lock.fmt(f),
+            None => f.write_str("<uninitialized>"),
+        }
+    }
+}
+
+/// A weak read-only reference to a value that may not have been initialized
+/// yet.
+///
+/// It can be upgraded as long as the [`OnceShared`] or any [`SharedReadLock`]
+/// of this module for the value exists. A [`crate::WeakReadLock`] can be
+/// obtained through [`SharedReadLock::get`] once the value has been
+/// initialized.
+pub struct WeakReadLock<T>(Weak<Slot<T>>);
+
+impl<T> WeakReadLock<T> {
+    /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`.
+    ///
+    /// Returns `None` if the `OnceShared` and all `SharedReadLock`s for the
+    /// value have been dropped.
+    pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
+        Weak::upgrade(&self.0).map(SharedReadLock)
+    }
+}
+
+impl<T> Clone for WeakReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(Weak::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for WeakReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakReadLock").finish_non_exhaustive()
+    }
+}
+
+/// Error returned from [`SharedReadLock::try_lock`].
+#[derive(Debug)]
+pub enum TryLockError {
+    /// The value has not been initialized.
+    Uninitialized,
+    /// The value is currently locked for writing.
+    Locked(crate::TryLockError),
+}
+
+impl fmt::Display for TryLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uninitialized => f.write_str("value has not been initialized"),
+            Self::Locked(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for TryLockError {}
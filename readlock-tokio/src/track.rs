@@ -0,0 +1,94 @@
+//! Bookkeeping for lock acquisitions, used by the debugging features.
+//!
+//! The per-value state and the instrumentation are shared with `readlock`.
+//! When none of the debugging features is enabled, the types in here are
+//! zero-sized and their methods do nothing.
+
+use std::{future::Future, panic::Location};
+
+#[cfg(feature = "hold-warnings")]
+use readlock::__private::HoldTracker;
+#[cfg(feature = "tracing")]
+use readlock::__private::Instrumentation;
+pub(crate) use readlock::__private::{LockKind, LockState};
+
+/// Per-acquisition state, stored in the lock guards.
+pub(crate) struct Acquisition {
+    #[cfg(feature = "tracing")]
+    tracing: Option<Box<Instrumentation>>,
+    #[cfg(feature = "hold-warnings")]
+    hold: Option<Box<HoldTracker>>,
+}
+
+impl Acquisition {
+    /// Start tracking an acquisition of the lock belonging to `state`.
+    ///
+    /// Must be called before trying to acquire the lock. `blocking` should be
+    /// `true` if the caller is going to wait for the lock to become available.
+    /// `location` is the caller's location, which has to be captured before
+    /// creating the future that acquires the lock.
+    #[allow(unused_variables)] // unused if no debugging feature is enabled
+    pub(crate) fn start(
+        state: &LockState,
+        kind: LockKind,
+        blocking: bool,
+        location: &'static Location<'static>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            tracing: Some(Box::new(Instrumentation::start_async(
+                state.name().map(AsRef::as_ref),
+                kind,
+                location,
+                blocking,
+            ))),
+            #[cfg(feature = "hold-warnings")]
+            hold: Some(Box::new(HoldTracker::start(
+                state.name(),
+                kind == LockKind::Write,
+                location,
+            ))),
+        }
+    }
+
+    /// An acquisition that is not tracked, for guards that are created from
+    /// their internal representation.
+    pub(crate) fn untracked() -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            tracing: None,
+            #[cfg(feature = "hold-warnings")]
+            hold: None,
+        }
+    }
+
+    /// Wait for the lock to be acquired by awaiting `future`.
+    pub(crate) async fn wait<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &self.tracing {
+            return tracing.wait_async(future).await;
+        }
+        future.await
+    }
+
+    /// Mark the lock as acquired.
+    pub(crate) fn acquired(&mut self) {
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &mut self.tracing {
+            tracing.acquired();
+        }
+        #[cfg(feature = "hold-warnings")]
+        if let Some(hold) = &mut self.hold {
+            hold.acquired();
+        }
+    }
+}
+
+impl Drop for Acquisition {
+    fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &self.tracing {
+            tracing.release();
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::future::Future;
+
+use readlock::traits::{AsyncReadLock, AsyncWriteOwner};
+
+#[cfg(feature = "lite")]
+use crate::lite;
+use crate::{Shared, SharedReadGuard, SharedReadLock, SharedWriteGuard};
+
+impl<T: ?Sized + Send + Sync> AsyncReadLock for SharedReadLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = SharedReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(&self) -> impl Future<Output = Self::Guard<'_>> + Send {
+        SharedReadLock::lock(self)
+    }
+}
+
+impl<T: ?Sized + Send + Sync> AsyncWriteOwner for Shared<T> {
+    type ReadLock = SharedReadLock<T>;
+    type WriteGuard<'a>
+        = SharedWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(this: &mut Self) -> impl Future<Output = Self::WriteGuard<'_>> + Send {
+        Shared::lock(this)
+    }
+
+    fn get_read_lock(this: &Self) -> Self::ReadLock {
+        Shared::get_read_lock(this)
+    }
+}
+
+#[cfg(feature = "lite")]
+impl<T: ?Sized + Send + Sync> AsyncReadLock for lite::SharedReadLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = SharedReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(&self) -> impl Future<Output = Self::Guard<'_>> + Send {
+        lite::SharedReadLock::lock(self)
+    }
+}
+
+#[cfg(feature = "lite")]
+impl<T: ?Sized + Send + Sync> AsyncWriteOwner for lite::Shared<T> {
+    type ReadLock = lite::SharedReadLock<T>;
+    type WriteGuard<'a>
+        = SharedWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(this: &mut Self) -> impl Future<Output = Self::WriteGuard<'_>> + Send {
+        lite::Shared::lock(this)
+    }
+
+    fn get_read_lock(this: &Self) -> Self::ReadLock {
+        lite::Shared::get_read_lock(this)
+    }
+}
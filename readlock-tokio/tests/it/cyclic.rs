@@ -0,0 +1,23 @@
+use readlock_tokio::{Shared, WeakReadLock};
+
+struct Node {
+    value: u8,
+    this: WeakReadLock<Node>,
+}
+
+#[tokio::test]
+async fn new_cyclic() {
+    let mut shared = Shared::new_cyclic(|weak| {
+        assert!(weak.upgrade().is_none());
+        Node { value: 1, this: weak.clone() }
+    });
+
+    let readlock = shared.this.upgrade().unwrap();
+    Shared::lock(&mut shared).await.value = 2;
+    assert_eq!(readlock.lock().await.value, 2);
+
+    drop(readlock);
+    let weak = shared.this.clone();
+    drop(shared);
+    assert!(weak.upgrade().is_none());
+}
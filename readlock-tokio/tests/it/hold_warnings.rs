@@ -0,0 +1,32 @@
+#![cfg(feature = "hold-warnings")]
+
+use std::sync::{Arc, Mutex};
+
+use readlock::hold_warnings;
+use readlock_tokio::Shared;
+use tokio::time::{sleep, Duration};
+
+#[tokio::test]
+async fn held_across_await() {
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    hold_warnings::set_threshold(Duration::from_millis(10));
+    hold_warnings::set_handler({
+        let warnings = warnings.clone();
+        move |hold| {
+            if hold.name == Some("hold_warnings::held_across_await") {
+                warnings.lock().unwrap().push((hold.write, hold.location.line()));
+            }
+        }
+    });
+
+    let mut shared = Shared::with_name(0, "hold_warnings::held_across_await");
+    let readlock = Shared::get_read_lock(&shared);
+
+    let line = line!() + 1;
+    let guard = Shared::lock(&mut shared).await;
+    sleep(Duration::from_millis(20)).await;
+    drop(guard);
+    drop(readlock.lock_owned().await);
+
+    assert_eq!(*warnings.lock().unwrap(), [(true, line)]);
+}
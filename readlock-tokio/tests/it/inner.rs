@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use readlock_tokio::{OwnedSharedReadGuard, Shared, SharedReadLock};
+use tokio::sync::RwLock;
+
+#[tokio::test]
+async fn round_trip() {
+    let mut shared = Shared::new(1);
+    *Shared::lock(&mut shared).await = 2;
+
+    let rwlock = Shared::into_inner(shared);
+    *rwlock.write().await += 1;
+
+    let shared = Shared::try_from_inner(rwlock).unwrap();
+    assert_eq!(*shared, 3);
+}
+
+#[tokio::test]
+async fn from_inner() {
+    let rwlock = Arc::new(RwLock::new(1));
+    let readlock = SharedReadLock::from_inner(Arc::clone(&rwlock));
+
+    *rwlock.write().await = 2;
+    assert_eq!(*readlock.lock().await, 2);
+    assert!(readlock.is_writer_alive());
+
+    let readlock = readlock.try_into_inner().unwrap_err();
+    drop(rwlock);
+    let rwlock = readlock.try_into_inner().unwrap();
+
+    let guard = OwnedSharedReadGuard::from_inner(rwlock.read_owned().await);
+    assert_eq!(*guard, 2);
+}
+
+#[tokio::test]
+async fn lock_owned() {
+    let shared = Shared::new(String::from("value"));
+    let readlock = Shared::get_read_lock(&shared);
+
+    let guard = readlock.lock_owned().await;
+    assert_eq!(Shared::read_count(&shared), 1);
+    assert!(Shared::unwrap(shared).is_err());
+    assert_eq!(*guard, "value");
+}
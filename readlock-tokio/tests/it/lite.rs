@@ -1,5 +1,7 @@
 #![cfg(feature = "lite")]
 
+use std::fmt::Display;
+
 use readlock_tokio::lite::Shared;
 use tokio::{
     task,
@@ -25,3 +27,62 @@ async fn parallel_read_write() {
         assert!(join_handle.is_finished());
     }
 }
+
+#[tokio::test]
+async fn upgrade_and_unwrap() {
+    let shared = Shared::new(String::from("foo"));
+    let readlock = Shared::get_read_lock(&shared);
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    let shared = Shared::unwrap(shared).unwrap_err();
+    let readlock = readlock.try_upgrade().unwrap_err();
+    drop(shared);
+
+    let shared = readlock.try_upgrade().unwrap();
+    assert_eq!(Shared::read_count(&shared), 0);
+    assert_eq!(Shared::unwrap(shared).unwrap(), "foo");
+}
+
+#[tokio::test]
+async fn lock_owned() {
+    let mut shared = Shared::new(1);
+    let guard = Shared::get_read_lock(&shared).lock_owned().await;
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    let join_handle = task::spawn(async move { *Shared::lock(&mut shared).await += 1 });
+    sleep(Duration::from_millis(5)).await;
+    assert_eq!(*guard, 1);
+    assert!(!join_handle.is_finished());
+
+    drop(guard);
+    join_handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn weak_read_lock() {
+    let shared = Shared::new(vec![1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(Shared::weak_count(&shared), 1);
+
+    let readlock = readlock.try_upgrade().unwrap_err();
+    assert_eq!(*weak.upgrade().unwrap().lock().await, [1, 2, 3]);
+
+    drop(readlock);
+    assert_eq!(Shared::unwrap(shared).unwrap(), [1, 2, 3]);
+    assert!(weak.upgrade().is_none());
+}
+
+#[tokio::test]
+async fn unsized_values() {
+    let mut shared: Shared<[i32]> = Shared::from([1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::lock(&mut shared).await[1] = 5;
+    assert_eq!(*readlock.lock().await, [1, 5, 3]);
+    drop(shared);
+    assert_eq!(*readlock.lock_owned().await, [1, 5, 3]);
+
+    let shared = Shared::new(1);
+    let readlock = readlock::coerce!(Shared::get_read_lock(&shared) => dyn Display + Send + Sync);
+    assert_eq!(readlock.lock().await.to_string(), "1");
+}
@@ -6,7 +6,17 @@ use tokio::{
     time::{sleep, Duration},
 };
 
+mod cyclic;
+mod hold_warnings;
+mod inner;
 mod lite;
+mod once;
+mod serde;
+mod tracing;
+mod traits;
+mod unique;
+mod unsize;
+mod writer;
 
 #[tokio::test]
 async fn parallel_read_write() {
@@ -0,0 +1,45 @@
+use readlock_tokio::{
+    once::{OnceShared, TryLockError},
+    Shared,
+};
+use tokio::task;
+
+#[tokio::test]
+async fn lock_waits_for_init() {
+    let once = OnceShared::new();
+    let readlock = once.get_read_lock();
+    assert!(!readlock.is_initialized());
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::Uninitialized)));
+
+    let reader = task::spawn({
+        let readlock = readlock.clone();
+        async move { *readlock.lock().await }
+    });
+    task::yield_now().await;
+    assert!(!reader.is_finished());
+
+    let mut shared = once.init(1);
+    assert_eq!(reader.await.unwrap(), 1);
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    *Shared::lock(&mut shared).await = 2;
+    assert_eq!(*readlock.downgrade().upgrade().unwrap().lock().await, 2);
+
+    drop(readlock);
+    assert_eq!(Shared::unwrap(shared).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn dropped_without_init() {
+    let once = OnceShared::<i32>::new();
+    let readlock = once.get_read_lock();
+    let reader = task::spawn({
+        let readlock = readlock.clone();
+        async move { *readlock.lock().await }
+    });
+    task::yield_now().await;
+
+    drop(once);
+    assert!(reader.await.unwrap_err().is_panic());
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::Uninitialized)));
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "serde")]
+
+use readlock_tokio::Shared;
+
+#[tokio::test]
+async fn roundtrip() {
+    let mut shared: Shared<Vec<u8>> = serde_json::from_str("[1,2,3]").unwrap();
+    Shared::lock(&mut shared).await.push(4);
+    assert_eq!(serde_json::to_string(&shared).unwrap(), "[1,2,3,4]");
+
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(serde_json::to_string(&readlock).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::to_string(&readlock.lock().await).unwrap(), "[1,2,3,4]");
+
+    let guard = Shared::lock(&mut shared).await;
+    assert!(serde_json::to_string(&readlock).is_err());
+    drop(guard);
+
+    assert_eq!(serde_json::to_string(&readlock.lock_owned().await).unwrap(), "[1,2,3,4]");
+    drop(shared);
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
+
+#[cfg(feature = "lite")]
+#[tokio::test]
+async fn lite() {
+    use readlock_tokio::lite;
+
+    let shared: lite::Shared<Vec<u8>> = serde_json::from_str("[1,2]").unwrap();
+    let readlock = lite::Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(serde_json::to_string(&shared).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&readlock).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "[1,2]");
+
+    drop((shared, readlock));
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use readlock_tokio::Shared;
+use tracing_subscriber::{fmt::format::FmtSpan, util::SubscriberInitExt};
+
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn lock_spans() {
+    let output = Output::default();
+    let _subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing_subscriber::filter::LevelFilter::TRACE)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .without_time()
+        .with_writer({
+            let output = output.clone();
+            move || output.clone()
+        })
+        .set_default();
+
+    let mut shared = Shared::with_name(1, "counter");
+    let readlock = Shared::get_read_lock(&shared);
+    *Shared::lock(&mut shared).await += 1;
+    drop(readlock.try_lock().unwrap());
+    drop(readlock.lock_owned().await);
+
+    assert!(format!("{shared:?}").starts_with("counter: RwLock"));
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<_> = output.lines().filter(|line| line.contains("close")).collect();
+    assert_eq!(lines.len(), 3, "{output}");
+    for (line, kind) in lines.iter().zip(["write", "read", "read"]) {
+        assert!(line.contains("tests/it/tracing.rs"), "{line}");
+        assert!(line.contains("readlock_tokio::lock{"), "{line}");
+        assert!(line.contains(r#"lock.name="counter""#), "{line}");
+        assert!(line.contains(&format!(r#"lock.kind="{kind}""#)), "{line}");
+        assert!(line.contains("wait=") && line.contains("hold="), "{line}");
+    }
+}
@@ -0,0 +1,21 @@
+use readlock_tokio::{
+    traits::{AsyncReadLock, AsyncWriteOwner},
+    Shared,
+};
+
+async fn push_and_read<W: AsyncWriteOwner<Target = Vec<u8>>>(mut shared: W) -> usize {
+    let readlock = W::get_read_lock(&shared);
+    W::lock(&mut shared).await.push(1);
+    len(&readlock).await
+}
+
+async fn len<R: AsyncReadLock<Target = Vec<u8>>>(readlock: &R) -> usize {
+    readlock.lock().await.len()
+}
+
+#[tokio::test]
+async fn generic_over_flavors() {
+    assert_eq!(push_and_read(Shared::new(vec![])).await, 1);
+    #[cfg(feature = "lite")]
+    assert_eq!(push_and_read(readlock_tokio::lite::Shared::new(vec![0])).await, 2);
+}
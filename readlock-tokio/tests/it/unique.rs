@@ -0,0 +1,70 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use readlock_tokio::Shared;
+
+#[tokio::test]
+async fn unwrap_when_unique() {
+    let shared = Shared::new(String::from("value"));
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+
+    let task = tokio::spawn(Shared::unwrap_when_unique(shared));
+    tokio::task::yield_now().await;
+
+    let guard = weak.upgrade().unwrap().lock_owned().await;
+    drop(readlock);
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    assert_eq!(*guard, "value");
+    drop(guard);
+
+    assert_eq!(task.await.unwrap(), "value");
+    assert!(weak.upgrade().is_none());
+}
+
+#[tokio::test]
+async fn unwrap_when_unique_timeout() {
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let shared =
+        Shared::unwrap_when_unique_timeout(shared, Duration::from_millis(10)).await.unwrap_err();
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    drop(readlock);
+    let data = Shared::unwrap_when_unique_timeout(shared, Duration::from_millis(10)).await;
+    assert_eq!(data.unwrap(), 1);
+}
+
+#[test]
+fn on_unique() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let shared = Shared::new(1);
+    Shared::on_unique(&shared, {
+        let calls = calls.clone();
+        move || {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    let readlock = Shared::get_read_lock(&shared);
+    let readlock2 = readlock.clone();
+    drop(readlock);
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    drop(readlock2);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    let readlock = Shared::get_read_lock(&shared);
+    drop(shared);
+    let shared = readlock.try_upgrade().unwrap();
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    drop(Shared::get_read_lock(&shared));
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
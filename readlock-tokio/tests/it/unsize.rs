@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use readlock_tokio::{Shared, SharedReadLock, WeakReadLock};
+
+#[tokio::test]
+async fn slices() {
+    let mut shared = Shared::<[u32]>::from([1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::lock(&mut shared).await[1] = 5;
+    assert_eq!(*readlock.lock().await, [1, 5, 3]);
+
+    drop(shared);
+    assert_eq!(*readlock.lock_owned().await, [1, 5, 3]);
+}
+
+#[tokio::test]
+async fn trait_objects() {
+    let mut shared = Shared::new(1);
+    let readlock: SharedReadLock<dyn Display + Send + Sync> =
+        readlock::coerce!(Shared::get_read_lock(&shared) => dyn Display + Send + Sync);
+    let weak: WeakReadLock<dyn Display + Send + Sync> =
+        readlock::coerce!(readlock.downgrade() => dyn Display + Send + Sync);
+
+    *Shared::lock(&mut shared).await = 2;
+    assert_eq!(readlock.lock().await.to_string(), "2");
+    assert_eq!(weak.upgrade().unwrap().lock().await.to_string(), "2");
+
+    let shared: Shared<dyn Display + Send + Sync> =
+        readlock::coerce!(shared => dyn Display + Send + Sync);
+    assert_eq!(shared.to_string(), "2");
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    drop(shared);
+    drop(readlock);
+    assert!(weak.upgrade().is_none());
+}
@@ -0,0 +1,27 @@
+use readlock_tokio::Shared;
+
+#[tokio::test]
+async fn writer_dropped() {
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    assert!(readlock.is_writer_alive());
+
+    let task = tokio::spawn({
+        let readlock = readlock.clone();
+        async move {
+            readlock.writer_dropped().await;
+            assert!(!readlock.is_writer_alive());
+        }
+    });
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+
+    drop(shared);
+    task.await.unwrap();
+
+    assert!(!readlock.is_writer_alive());
+    readlock.writer_dropped().await;
+
+    let shared = readlock.try_upgrade().unwrap();
+    assert!(Shared::get_read_lock(&shared).is_writer_alive());
+}
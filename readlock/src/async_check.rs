@@ -0,0 +1,107 @@
+//! Detection of blocking lock acquisitions inside async tasks, enabled by the
+//! `async-blocking-check` feature.
+//!
+//! While this feature is enabled, every blocking acquisition of a
+//! [`SharedReadGuard`] or [`SharedWriteGuard`] checks whether it happens inside
+//! the context of a tokio runtime, i.e. in one of its tasks or in a future
+//! passed to `block_on`, where it would block the runtime's thread instead of
+//! letting other tasks run while waiting. If it does, the acquisition panics
+//! or logs a warning, depending on the configured [`Action`].
+//!
+//! Values that are locked from async code should use `readlock-tokio` instead.
+//!
+//! Tasks spawned through `tokio::task::spawn_blocking`, code running in
+//! `tokio::task::block_in_place` and code running while a runtime is entered
+//! through `Runtime::enter` can't be told apart from async code, so blocking
+//! acquisitions there have to be wrapped in [`allow_blocking`].
+//!
+//! [`SharedReadGuard`]: crate::SharedReadGuard
+//! [`SharedWriteGuard`]: crate::SharedWriteGuard
+
+use std::{
+    cell::Cell,
+    panic::Location,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+/// What to do when a blocking acquisition inside an async task is detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    /// Panic.
+    ///
+    /// This is the default in builds with debug assertions.
+    Panic,
+    /// Log a warning through `log`, or emit it as a `tracing` event if the
+    /// `tracing` feature is enabled.
+    ///
+    /// This is the default in builds without debug assertions.
+    Warn,
+    /// Do nothing.
+    Ignore,
+}
+
+impl Action {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Panic,
+            1 => Self::Warn,
+            _ => Self::Ignore,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Panic => 0,
+            Self::Warn => 1,
+            Self::Ignore => 2,
+        }
+    }
+}
+
+static ACTION: AtomicU8 = AtomicU8::new(if cfg!(debug_assertions) { 0 } else { 1 });
+
+thread_local! {
+    static ALLOW_BLOCKING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set what to do when a blocking acquisition inside an async task is
+/// detected.
+pub fn set_action(action: Action) {
+    ACTION.store(action.to_u8(), Ordering::Relaxed);
+}
+
+/// Run `f`, allowing blocking acquisitions inside of it even if it runs inside
+/// an async task.
+pub fn allow_blocking<R>(f: impl FnOnce() -> R) -> R {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ALLOW_BLOCKING.set(self.0);
+        }
+    }
+
+    let _reset = Reset(ALLOW_BLOCKING.replace(true));
+    f()
+}
+
+pub(crate) fn check(location: &'static Location<'static>) {
+    if ALLOW_BLOCKING.get() || tokio::runtime::Handle::try_current().is_err() {
+        return;
+    }
+
+    let message = format_args!(
+        "blocking lock acquisition at {location} inside an async task, which blocks the \
+         runtime's thread; use `readlock-tokio` for values that are locked from async \
+         code, or `readlock::async_check::allow_blocking` if this is intended"
+    );
+    match Action::from_u8(ACTION.load(Ordering::Relaxed)) {
+        Action::Panic => panic!("{message}"),
+        #[cfg(feature = "tracing")]
+        Action::Warn => tracing::warn!("{message}"),
+        #[cfg(not(feature = "tracing"))]
+        Action::Warn => log::warn!("{message}"),
+        Action::Ignore => {}
+    }
+}
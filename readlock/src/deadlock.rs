@@ -0,0 +1,328 @@
+//! Deadlock detection, enabled by the `deadlock-detection` feature.
+//!
+//! While this feature is enabled, every blocking acquisition of a
+//! [`SharedReadGuard`] or [`SharedWriteGuard`] is checked against the guards
+//! that are already held by the current thread. The acquisition panics if
+//!
+//! - the same value is already locked by the current thread, which either
+//!   deadlocks right away or can deadlock as soon as the `Shared` writer tries
+//!   to lock the value in between two read locks, or
+//! - a different value is already locked by the current thread, but the two
+//!   values have previously been locked in the opposite order (a lock order
+//!   inversion).
+//!
+//! The panic message includes the source locations of both acquisitions.
+//!
+//! Additionally, [`check`] and [`spawn_checker`] can be used to find threads
+//! that are currently deadlocked, waiting on each other.
+//!
+//! All of this has considerable runtime overhead and is meant to be used in
+//! debug builds or tests only.
+//!
+//! [`SharedReadGuard`]: crate::SharedReadGuard
+//! [`SharedWriteGuard`]: crate::SharedWriteGuard
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    panic::Location,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, OnceLock,
+    },
+    thread::{self, JoinHandle, ThreadId},
+    time::Duration,
+};
+
+use crate::{lock_unpoisoned, track::LockKind};
+
+type SourceLocation = &'static Location<'static>;
+
+/// Unique identifier of a `Shared` value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct LockId(u64);
+
+impl LockId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct HeldLock {
+    id: LockId,
+    kind: LockKind,
+    location: SourceLocation,
+}
+
+#[derive(Default)]
+struct ThreadState {
+    held: Vec<HeldLock>,
+    waiting: Option<HeldLock>,
+}
+
+#[derive(Default)]
+struct Registry {
+    threads: HashMap<ThreadId, ThreadState>,
+    /// Lock order graph: an entry `b` in `order[a]` means that `b` has been
+    /// locked while `a` was held, at the given locations.
+    order: HashMap<LockId, HashMap<LockId, (SourceLocation, SourceLocation)>>,
+}
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+fn registry() -> MutexGuard<'static, Registry> {
+    lock_unpoisoned(REGISTRY.get_or_init(Default::default))
+}
+
+/// State of a single lock acquisition.
+pub(crate) struct Acquisition {
+    lock: HeldLock,
+    acquired: bool,
+}
+
+impl Acquisition {
+    pub(crate) fn start(
+        id: LockId,
+        kind: LockKind,
+        location: SourceLocation,
+        blocking: bool,
+    ) -> Self {
+        let lock = HeldLock { id, kind, location };
+        if blocking {
+            let mut registry = registry();
+            if let Err(message) = registry.start_waiting(lock) {
+                drop(registry);
+                panic!("{message}");
+            }
+        }
+
+        Self { lock, acquired: false }
+    }
+
+    pub(crate) fn acquired(&mut self) {
+        let mut registry = registry();
+        let thread = registry.threads.entry(thread::current().id()).or_default();
+        thread.waiting = None;
+        thread.held.push(self.lock);
+        self.acquired = true;
+    }
+
+    pub(crate) fn release(&self) {
+        let mut registry = registry();
+        let thread_id = thread::current().id();
+        let Some(thread) = registry.threads.get_mut(&thread_id) else { return };
+
+        if self.acquired {
+            if let Some(idx) = thread.held.iter().rposition(|held| held.id == self.lock.id) {
+                thread.held.remove(idx);
+            }
+        } else {
+            thread.waiting = None;
+        }
+
+        if thread.held.is_empty() && thread.waiting.is_none() {
+            registry.threads.remove(&thread_id);
+        }
+    }
+}
+
+impl Registry {
+    fn start_waiting(&mut self, lock: HeldLock) -> Result<(), String> {
+        let thread_id = thread::current().id();
+        let held = self.threads.get(&thread_id).map(|t| t.held.clone()).unwrap_or_default();
+
+        for prev in &held {
+            if prev.id == lock.id {
+                return Err(recursive_lock_message(prev, &lock));
+            }
+            if let Some((first, second)) = self.find_order(lock.id, prev.id) {
+                return Err(format!(
+                    "lock order inversion: locking a `Shared` value at {} while holding another \
+                     one locked at {}, but they have previously been locked in the opposite \
+                     order, at {first} and then at {second}",
+                    lock.location, prev.location,
+                ));
+            }
+        }
+
+        for prev in &held {
+            self.order
+                .entry(prev.id)
+                .or_default()
+                .entry(lock.id)
+                .or_insert((prev.location, lock.location));
+        }
+        self.threads.entry(thread_id).or_default().waiting = Some(lock);
+
+        Ok(())
+    }
+
+    /// Search the lock order graph for a path from `from` to `to`, returning
+    /// the locations of the first edge on that path if there is one.
+    fn find_order(&self, from: LockId, to: LockId) -> Option<(SourceLocation, SourceLocation)> {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<_> =
+            self.order.get(&from)?.iter().map(|(&next, &locations)| (next, locations)).collect();
+
+        while let Some((id, locations)) = stack.pop() {
+            if id == to {
+                return Some(locations);
+            }
+            if visited.insert(id) {
+                if let Some(edges) = self.order.get(&id) {
+                    stack.extend(edges.keys().map(|&next| (next, locations)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Threads that are blocking the given waiting thread from making
+    /// progress.
+    fn blockers(&self, thread_id: ThreadId, waiting: &HeldLock) -> Vec<ThreadId> {
+        self.threads
+            .iter()
+            .filter(|&(&other_id, other)| {
+                other_id != thread_id
+                    && (other.held.iter().any(|held| {
+                        held.id == waiting.id
+                            && (waiting.kind == LockKind::Write || held.kind == LockKind::Write)
+                    }) || (waiting.kind == LockKind::Read
+                        // `std::sync::RwLock` may block new readers while a
+                        // writer is waiting.
+                        && other.waiting.is_some_and(|w| {
+                            w.id == waiting.id && w.kind == LockKind::Write
+                        })))
+            })
+            .map(|(&other_id, _)| other_id)
+            .collect()
+    }
+
+    fn find_deadlocks(&self) -> Vec<Vec<DeadlockedThread>> {
+        let edges: HashMap<_, _> = self
+            .threads
+            .iter()
+            .filter_map(|(&id, thread)| Some((id, self.blockers(id, thread.waiting.as_ref()?))))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut cycles = Vec::new();
+        for &start in edges.keys() {
+            if seen.contains(&start) {
+                continue;
+            }
+
+            // Follow the chain of blocked threads depth-first until a thread
+            // on the current path shows up again.
+            let mut path = vec![start];
+            let mut iters = vec![edges[&start].iter()];
+            while let Some(iter) = iters.last_mut() {
+                let Some(&next) = iter.next() else {
+                    iters.pop();
+                    seen.insert(path.pop().unwrap());
+                    continue;
+                };
+
+                if let Some(pos) = path.iter().position(|&id| id == next) {
+                    cycles.push(path[pos..].iter().map(|id| self.deadlocked_thread(*id)).collect());
+                    seen.extend(path.iter().copied());
+                    break;
+                }
+                if !seen.contains(&next) {
+                    if let Some(next_edges) = edges.get(&next) {
+                        path.push(next);
+                        iters.push(next_edges.iter());
+                    }
+                }
+            }
+        }
+
+        cycles
+    }
+
+    fn deadlocked_thread(&self, thread_id: ThreadId) -> DeadlockedThread {
+        let thread = &self.threads[&thread_id];
+        DeadlockedThread {
+            thread_id,
+            waiting_at: thread.waiting.expect("deadlocked threads are waiting").location,
+            holding: thread.held.iter().map(|held| held.location).collect(),
+        }
+    }
+}
+
+fn recursive_lock_message(prev: &HeldLock, lock: &HeldLock) -> String {
+    let (prev_location, location) = (prev.location, lock.location);
+    match (prev.kind, lock.kind) {
+        (LockKind::Read, LockKind::Read) => format!(
+            "recursive read lock: a `Shared` value locked at {location} is already locked for \
+             reading by the same thread at {prev_location}; this deadlocks if the `Shared` \
+             writer tries to lock it in between"
+        ),
+        (LockKind::Read, LockKind::Write) => format!(
+            "deadlock: a `Shared` value locked for writing at {location} is already locked \
+             for reading by the same thread at {prev_location}"
+        ),
+        (LockKind::Write, _) => format!(
+            "deadlock: a `Shared` value locked at {location} is already locked for writing by \
+             the same thread at {prev_location}"
+        ),
+    }
+}
+
+/// Remove a value that is being deallocated from the lock order graph.
+pub(crate) fn forget(id: LockId) {
+    let mut registry = registry();
+    registry.order.remove(&id);
+    for edges in registry.order.values_mut() {
+        edges.remove(&id);
+    }
+}
+
+/// A thread that is part of a deadlock.
+#[derive(Clone)]
+pub struct DeadlockedThread {
+    /// The ID of the thread.
+    pub thread_id: ThreadId,
+    /// The source location of the lock acquisition the thread is blocked on.
+    pub waiting_at: &'static Location<'static>,
+    /// The source locations where the locks currently held by the thread were
+    /// acquired.
+    pub holding: Vec<&'static Location<'static>>,
+}
+
+impl fmt::Debug for DeadlockedThread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadlockedThread")
+            .field("thread_id", &self.thread_id)
+            .field("waiting_at", &format_args!("{}", self.waiting_at))
+            .field("holding", &self.holding.iter().map(|l| l.to_string()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Find threads that are currently deadlocked.
+///
+/// Returns one list of threads per cycle of threads waiting on each other.
+pub fn check() -> Vec<Vec<DeadlockedThread>> {
+    registry().find_deadlocks()
+}
+
+/// Spawn a background thread that runs [`check`] every `interval`, and calls
+/// `on_deadlock` for every deadlock found.
+///
+/// The thread runs until the process exits.
+pub fn spawn_checker(
+    interval: Duration,
+    on_deadlock: impl Fn(Vec<DeadlockedThread>) + Send + 'static,
+) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name("readlock-deadlock-checker".to_owned())
+        .spawn(move || loop {
+            thread::sleep(interval);
+            check().into_iter().for_each(&on_deadlock);
+        })
+        .expect("failed to spawn deadlock checker thread")
+}
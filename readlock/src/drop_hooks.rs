@@ -0,0 +1,34 @@
+//! Callbacks that run when a shared value is dropped.
+
+use std::{mem, sync::Mutex};
+
+use crate::lock_unpoisoned;
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+/// Runs the registered hooks when dropped.
+pub(crate) struct DropHooks(Mutex<Vec<Hook>>);
+
+impl DropHooks {
+    pub(crate) fn new() -> Self {
+        Self(Mutex::new(Vec::new()))
+    }
+
+    pub(crate) fn push(&self, hook: impl FnOnce() + Send + 'static) {
+        lock_unpoisoned(&self.0).push(Box::new(hook));
+    }
+
+    /// Run the registered hooks.
+    pub(crate) fn run(&self) {
+        let hooks = mem::take(&mut *lock_unpoisoned(&self.0));
+        for hook in hooks {
+            hook();
+        }
+    }
+}
+
+impl Drop for DropHooks {
+    fn drop(&mut self) {
+        self.run();
+    }
+}
@@ -0,0 +1,131 @@
+//! Warnings for lock guards that are held for too long, enabled by the
+//! `hold-warnings` feature.
+//!
+//! While this feature is enabled, every [`SharedReadGuard`] and
+//! [`SharedWriteGuard`] records the time and location it was acquired at, as
+//! well as a backtrace if backtraces are enabled through the `RUST_BACKTRACE`
+//! or `RUST_LIB_BACKTRACE` environment variables. If it is dropped after being
+//! held for longer than the [threshold][set_threshold], a warning is passed to
+//! the [handler][set_handler], which by default logs it through `log`, or emits
+//! it as a `tracing` event if the `tracing` feature is enabled as well.
+//!
+//! Guards that are never released are only reported while
+//! [`spawn_watchdog`] is running, which periodically checks the guards
+//! acquired since it was started.
+//!
+//! The same applies to the guards of `readlock-tokio` if its `hold-warnings`
+//! feature is enabled. For those, the time a guard is held includes any time
+//! spent in `.await`s while holding it.
+//!
+//! Capturing a backtrace for every acquisition is expensive, so this is meant
+//! to be used in debug builds or tests only, and backtraces should only be
+//! enabled while looking for the cause of a warning.
+//!
+//! [`SharedReadGuard`]: crate::SharedReadGuard
+//! [`SharedWriteGuard`]: crate::SharedWriteGuard
+
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    fmt,
+    panic::Location,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+pub(crate) mod tracker;
+
+type Handler = Arc<dyn Fn(&LongHold<'_>) + Send + Sync>;
+
+static THRESHOLD_NANOS: AtomicU64 = AtomicU64::new(1_000_000_000);
+static HANDLER: RwLock<Option<Handler>> = RwLock::new(None);
+
+/// Set the time after which holding a guard results in a warning.
+///
+/// Defaults to one second.
+pub fn set_threshold(threshold: Duration) {
+    let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+    THRESHOLD_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Get the time after which holding a guard results in a warning.
+pub fn threshold() -> Duration {
+    Duration::from_nanos(THRESHOLD_NANOS.load(Ordering::Relaxed))
+}
+
+/// Set the function that is called for guards that were held for too long,
+/// replacing the default handler.
+pub fn set_handler(handler: impl Fn(&LongHold<'_>) + Send + Sync + 'static) {
+    *HANDLER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(handler));
+}
+
+/// Information about a guard that was held for longer than the threshold.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LongHold<'a> {
+    /// The name of the value, if it was created with a name.
+    pub name: Option<&'a str>,
+    /// Whether the guard was a write guard.
+    pub write: bool,
+    /// The location the guard was acquired at.
+    pub location: &'static Location<'static>,
+    /// How long the guard was held.
+    pub held_for: Duration,
+    /// Whether the guard is still held, if it was reported by the
+    /// [watchdog][spawn_watchdog]. In that case, it is reported again once it
+    /// is released.
+    pub still_held: bool,
+    /// A backtrace captured when the guard was acquired, if backtraces are
+    /// enabled. See [`Backtrace::capture`].
+    pub backtrace: &'a Backtrace,
+}
+
+impl fmt::Display for LongHold<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = if self.write { "SharedWriteGuard" } else { "SharedReadGuard" };
+        write!(f, "{guard}")?;
+        if let Some(name) = self.name {
+            write!(f, " for `{name}`")?;
+        }
+        write!(f, " acquired at {}", self.location)?;
+        let held = if self.still_held { "has been held" } else { "was held" };
+        write!(f, " {held} for {:?}", self.held_for)?;
+        if self.backtrace.status() == BacktraceStatus::Captured {
+            write!(f, ", backtrace:\n{}", self.backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+/// Spawn a thread that reports guards which are still held after the
+/// [threshold][set_threshold], checking every `interval`.
+///
+/// Only guards acquired after this was called are checked. Each of them is
+/// reported at most once by the watchdog, and again when it is released.
+///
+/// The thread runs until the process exits.
+pub fn spawn_watchdog(interval: Duration) -> JoinHandle<()> {
+    tracker::WATCHDOG_RUNNING.store(true, Ordering::Relaxed);
+    thread::Builder::new()
+        .name("readlock-hold-watchdog".to_owned())
+        .spawn(move || loop {
+            thread::sleep(interval);
+            tracker::check();
+        })
+        .expect("failed to spawn hold watchdog thread")
+}
+
+fn report(hold: &LongHold<'_>) {
+    // Not called while holding the lock, so the handler can replace itself.
+    let handler = HANDLER.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match handler {
+        Some(handler) => handler(hold),
+        #[cfg(feature = "tracing")]
+        None => tracing::warn!("{hold}"),
+        #[cfg(not(feature = "tracing"))]
+        None => log::warn!("{hold}"),
+    }
+}
@@ -0,0 +1,107 @@
+//! Per-guard state of the `hold-warnings` feature, and the registry of held
+//! guards scanned by the [watchdog][super::spawn_watchdog].
+
+use std::{
+    backtrace::Backtrace,
+    borrow::Cow,
+    collections::HashMap,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, OnceLock,
+    },
+    time::Instant,
+};
+
+use super::{report, threshold, LongHold};
+use crate::lock_unpoisoned;
+
+/// The guards that are currently held, registered while a watchdog is running.
+static HELD: OnceLock<Mutex<HashMap<usize, Arc<Held>>>> = OnceLock::new();
+
+fn held() -> MutexGuard<'static, HashMap<usize, Arc<Held>>> {
+    lock_unpoisoned(HELD.get_or_init(Default::default))
+}
+pub(super) static WATCHDOG_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug)]
+struct Held {
+    name: Option<Cow<'static, str>>,
+    write: bool,
+    location: &'static Location<'static>,
+    acquired: OnceLock<(Instant, Backtrace)>,
+    /// Whether the watchdog has reported the guard already.
+    reported: AtomicBool,
+}
+
+impl Held {
+    fn report(&self, still_held: bool) {
+        let Some((acquired_at, backtrace)) = self.acquired.get() else { return };
+        let held_for = acquired_at.elapsed();
+        if held_for > threshold() {
+            report(&LongHold {
+                name: self.name.as_deref(),
+                write: self.write,
+                location: self.location,
+                held_for,
+                still_held,
+                backtrace,
+            });
+        }
+    }
+}
+
+/// Tracks how long a guard is held. Used by `readlock-tokio`.
+#[derive(Debug)]
+pub struct HoldTracker(Arc<Held>);
+
+impl HoldTracker {
+    /// Start tracking an acquisition, before the lock is acquired.
+    pub fn start(
+        name: Option<&Cow<'static, str>>,
+        write: bool,
+        location: &'static Location<'static>,
+    ) -> Self {
+        Self(Arc::new(Held {
+            name: name.cloned(),
+            write,
+            location,
+            acquired: OnceLock::new(),
+            reported: AtomicBool::new(false),
+        }))
+    }
+
+    /// Mark the lock as acquired.
+    pub fn acquired(&mut self) {
+        let _ = self.0.acquired.set((Instant::now(), Backtrace::capture()));
+        if WATCHDOG_RUNNING.load(Ordering::Relaxed) {
+            held().insert(self.key(), self.0.clone());
+        }
+    }
+
+    fn key(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+}
+
+impl Drop for HoldTracker {
+    fn drop(&mut self) {
+        if WATCHDOG_RUNNING.load(Ordering::Relaxed) {
+            held().remove(&self.key());
+        }
+        self.0.report(false);
+    }
+}
+
+/// Report the guards that have been held for longer than the threshold, once
+/// per guard.
+pub(super) fn check() {
+    let held: Vec<_> = held().values().cloned().collect();
+    for held in held {
+        let over_threshold =
+            held.acquired.get().is_some_and(|(acquired_at, _)| acquired_at.elapsed() > threshold());
+        if over_threshold && !held.reported.swap(true, Ordering::Relaxed) {
+            held.report(true);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! `tracing` instrumentation of lock acquisitions, enabled by the `tracing`
+//! feature.
+
+use std::{
+    future::Future,
+    panic::Location,
+    time::{Duration, Instant},
+};
+
+use tracing::{field, Instrument as _, Span};
+
+use crate::track::LockKind;
+
+/// Create the span for a lock acquisition. The span name has to be a literal.
+macro_rules! lock_span {
+    ($span_name:literal, $name:expr, $kind:expr, $location:expr, $blocking:expr) => {
+        tracing::trace_span!(
+            $span_name,
+            lock.name = $name,
+            lock.kind = $kind.as_str(),
+            lock.blocking = $blocking,
+            code.location = %$location,
+            wait = field::Empty,
+            hold = field::Empty,
+        )
+    };
+}
+
+/// Instrumentation of a single lock acquisition. Used by `readlock-tokio`.
+///
+/// The span is created when starting to acquire the lock, entered while
+/// waiting for it and closed when the guard is dropped.
+#[derive(Debug)]
+pub struct Acquisition {
+    span: Span,
+    started_at: Instant,
+    acquired_at: Option<Instant>,
+}
+
+impl Acquisition {
+    pub(crate) fn start(
+        name: Option<&str>,
+        kind: LockKind,
+        location: &'static Location<'static>,
+        blocking: bool,
+    ) -> Self {
+        Self::new(lock_span!("readlock::lock", name, kind, location, blocking))
+    }
+
+    /// Start instrumenting an acquisition of a `readlock-tokio` lock, before
+    /// the lock is acquired.
+    pub fn start_async(
+        name: Option<&str>,
+        kind: LockKind,
+        location: &'static Location<'static>,
+        blocking: bool,
+    ) -> Self {
+        Self::new(lock_span!("readlock_tokio::lock", name, kind, location, blocking))
+    }
+
+    fn new(span: Span) -> Self {
+        Self { span, started_at: Instant::now(), acquired_at: None }
+    }
+
+    pub(crate) fn wait<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.span.in_scope(f)
+    }
+
+    /// Wait for the lock to be acquired by awaiting `future`, inside of the
+    /// span.
+    pub async fn wait_async<F: Future>(&self, future: F) -> F::Output {
+        future.instrument(self.span.clone()).await
+    }
+
+    /// Mark the lock as acquired.
+    pub fn acquired(&mut self) {
+        let now = Instant::now();
+        let wait = now - self.started_at;
+        self.span.record("wait", field::debug(wait));
+        tracing::trace!(parent: &self.span, ?wait, "lock acquired");
+        self.acquired_at = Some(now);
+    }
+
+    /// Mark the lock as released.
+    pub fn release(&self) {
+        let Some(acquired_at) = self.acquired_at else { return };
+        let hold: Duration = acquired_at.elapsed();
+        self.span.record("hold", field::debug(hold));
+        tracing::trace!(parent: &self.span, ?hold, "lock released");
+    }
+}
@@ -0,0 +1,181 @@
+//! Handing the write access to a value between multiple owners.
+
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    mem::{self, ManuallyDrop},
+    ops,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+};
+
+use crate::{
+    lock_unpoisoned, wait_while_unpoisoned, Shared, SharedReadLock, SharedWriteGuard, WeakReadLock,
+};
+
+/// A place to keep the [`Shared`] for a value while it is not being used, so
+/// multiple owners can take turns being its writer.
+///
+/// The `Shared` is borrowed through [`WriterLease`]s, which return it to the
+/// slot when dropped. Only one lease can exist at a time, so there is still
+/// only a single writer at any moment. [`SharedReadLock`]s obtained through
+/// [`get_read_lock`][Self::get_read_lock] are not affected by leases being
+/// handed out and returned.
+///
+/// # Examples
+///
+/// ```
+/// use readlock::{Shared, WriterLease, WriterSlot};
+///
+/// let slot = WriterSlot::new(Shared::new(1));
+/// let readlock = slot.get_read_lock();
+///
+/// let mut lease = slot.acquire();
+/// assert!(slot.try_acquire().is_none());
+/// *WriterLease::lock(&mut lease) = 2;
+/// drop(lease);
+///
+/// assert_eq!(*readlock.lock(), 2);
+/// assert!(slot.try_acquire().is_some());
+/// ```
+pub struct WriterSlot<T: ?Sized> {
+    state: Mutex<State<T>>,
+    returned: Condvar,
+    weak: WeakReadLock<T>,
+}
+
+struct State<T: ?Sized> {
+    shared: Option<Shared<T>>,
+    wakers: Vec<Waker>,
+}
+
+impl<T: ?Sized> WriterSlot<T> {
+    /// Create a new `WriterSlot` holding `shared`.
+    #[track_caller]
+    pub fn new(shared: Shared<T>) -> Self {
+        let weak = Shared::downgrade(&shared);
+        Self {
+            state: Mutex::new(State { shared: Some(shared), wakers: Vec::new() }),
+            returned: Condvar::new(),
+            weak,
+        }
+    }
+
+    /// Acquire the write access, blocking the current thread until no other
+    /// [`WriterLease`] for this slot exists.
+    pub fn acquire(&self) -> WriterLease<'_, T> {
+        let mut state =
+            wait_while_unpoisoned(&self.returned, self.state(), |state| state.shared.is_none());
+        self.lease(&mut state).expect("checked above")
+    }
+
+    /// Acquire the write access, waiting until no other [`WriterLease`] for
+    /// this slot exists.
+    ///
+    /// Unlike [`acquire`][Self::acquire], this doesn't block the current
+    /// thread, and works with any async runtime.
+    pub fn acquire_async(&self) -> impl Future<Output = WriterLease<'_, T>> {
+        poll_fn(|cx| {
+            let mut state = self.state();
+            if let Some(lease) = self.lease(&mut state) {
+                return Poll::Ready(lease);
+            }
+
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+
+    /// Try to acquire the write access.
+    ///
+    /// Returns `None` if another [`WriterLease`] for this slot exists.
+    pub fn try_acquire(&self) -> Option<WriterLease<'_, T>> {
+        self.lease(&mut self.state())
+    }
+
+    /// Get a [`SharedReadLock`] for accessing the value read-only from
+    /// elsewhere.
+    #[track_caller]
+    pub fn get_read_lock(&self) -> SharedReadLock<T> {
+        self.weak.upgrade().expect("the `Shared` is kept alive by the slot or a lease")
+    }
+
+    /// Take the [`Shared`] out of the slot.
+    pub fn into_inner(self) -> Shared<T> {
+        let state = self.state.into_inner().unwrap_or_else(PoisonError::into_inner);
+        state.shared.expect("leases borrow the slot, so none can exist anymore")
+    }
+
+    fn lease(&self, state: &mut State<T>) -> Option<WriterLease<'_, T>> {
+        let shared = state.shared.take()?;
+        Some(WriterLease { slot: self, shared: ManuallyDrop::new(shared) })
+    }
+
+    fn release(&self, shared: Shared<T>) {
+        let wakers = {
+            let mut state = self.state();
+            state.shared = Some(shared);
+            mem::take(&mut state.wakers)
+        };
+        self.returned.notify_one();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<T>> {
+        lock_unpoisoned(&self.state)
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for WriterSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriterSlot").finish_non_exhaustive()
+    }
+}
+
+/// The write access to a value, borrowed from a [`WriterSlot`].
+///
+/// Dereferences to the [`Shared`] for the value. Mutating the value works
+/// through [`WriterLease::lock`], since the `Shared` itself can't be replaced.
+///
+/// The `Shared` is returned to the slot when this is dropped.
+pub struct WriterLease<'a, T: ?Sized> {
+    slot: &'a WriterSlot<T>,
+    shared: ManuallyDrop<Shared<T>>,
+}
+
+impl<T: ?Sized> WriterLease<'_, T> {
+    /// Lock the value to be able to mutate it, blocking the current thread
+    /// until the operation succeeds.
+    ///
+    /// This is the same as [`Shared::lock`].
+    #[track_caller]
+    pub fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
+        Shared::lock(&mut this.shared)
+    }
+}
+
+impl<T: ?Sized> ops::Deref for WriterLease<'_, T> {
+    type Target = Shared<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.shared
+    }
+}
+
+impl<T: ?Sized> Drop for WriterLease<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: `self.shared` is not used again.
+        let shared = unsafe { ManuallyDrop::take(&mut self.shared) };
+        self.slot.release(shared);
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for WriterLease<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.shared.fmt(f)
+    }
+}
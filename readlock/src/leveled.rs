@@ -0,0 +1,348 @@
+//! Versions of `Shared`, `SharedReadLock` and `WeakReadLock` that carry a lock
+//! level, which is used to enforce a consistent locking order at compile time.
+//!
+//! Lock levels are types, declared in order from the first one to be locked
+//! to the last one using the [`lock_levels!`][crate::lock_levels] macro.
+//! Locking any value requires a [`LockToken`] for a level that comes before the
+//! value's level. The token is borrowed for as long as the resulting guard is
+//! alive, and a new token for the value's level is returned alongside the
+//! guard.
+//!
+//! Since all locks are acquired in the same order this way, two threads can't
+//! end up waiting on each other. Each thread can obtain one initial token
+//! through [`ThreadToken::get`].
+//!
+//! ```
+//! use readlock::leveled::{Shared, ThreadToken};
+//!
+//! readlock::lock_levels!(pub Config > Cache);
+//!
+//! let config = Shared::<_, Config>::new(String::from("config"));
+//! let cache = Shared::<_, Cache>::new(Vec::<String>::new());
+//! let config_reader = Shared::get_read_lock(&config);
+//! let cache_reader = Shared::get_read_lock(&cache);
+//!
+//! let mut thread_token = ThreadToken::get().unwrap();
+//! let mut token = thread_token.token();
+//! let (config_guard, mut token) = config_reader.lock(&mut token);
+//! let (cache_guard, _token) = cache_reader.lock(&mut token);
+//! ```
+//!
+//! Locking in the wrong order is a compile error:
+//!
+//! ```compile_fail
+//! # use readlock::leveled::{Shared, ThreadToken};
+//! # readlock::lock_levels!(pub Config > Cache);
+//! # let config = Shared::<_, Config>::new(String::from("config"));
+//! # let cache = Shared::<_, Cache>::new(Vec::<String>::new());
+//! # let config_reader = Shared::get_read_lock(&config);
+//! # let cache_reader = Shared::get_read_lock(&cache);
+//! let mut thread_token = ThreadToken::get().unwrap();
+//! let mut token = thread_token.token();
+//! let (cache_guard, mut token) = cache_reader.lock(&mut token);
+//! let (config_guard, _token) = config_reader.lock(&mut token);
+//! ```
+
+use std::{cell::Cell, fmt, marker::PhantomData, ops, sync::TryLockResult};
+
+use crate::{SharedReadGuard, SharedWriteGuard};
+
+/// A lock level.
+///
+/// Usually implemented through [`lock_levels!`][crate::lock_levels].
+pub trait LockLevel {}
+
+/// Marker trait for lock levels that can be locked while level `L` is held.
+///
+/// Usually implemented through [`lock_levels!`][crate::lock_levels].
+pub trait LockAfter<L>: LockLevel {}
+
+/// The level of the initial [`LockToken`], before any lock is held.
+#[derive(Debug)]
+pub enum Unlocked {}
+
+impl LockLevel for Unlocked {}
+
+/// Declare a list of lock levels.
+///
+/// The levels are listed in the order they must be locked in, separated by
+/// `>`. Each level becomes an uninhabited `enum` with the given visibility.
+///
+/// ```
+/// readlock::lock_levels!(pub(crate) Config > Connections > Cache);
+/// ```
+#[macro_export]
+macro_rules! lock_levels {
+    ($vis:vis $($level:ident)>+) => {
+        $(
+            #[doc = concat!("Lock level `", stringify!($level), "`.")]
+            #[derive(Debug)]
+            $vis enum $level {}
+
+            impl $crate::leveled::LockLevel for $level {}
+            impl $crate::leveled::LockAfter<$crate::leveled::Unlocked> for $level {}
+        )+
+
+        $crate::__lock_level_order!($($level)+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __lock_level_order {
+    ($first:ident $($rest:ident)*) => {
+        $( impl $crate::leveled::LockAfter<$first> for $rest {} )*
+        $crate::__lock_level_order!($($rest)*);
+    };
+    () => {};
+}
+
+thread_local! {
+    static THREAD_TOKEN_TAKEN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The initial lock token of a thread.
+///
+/// Only one `ThreadToken` can exist per thread at a time.
+#[derive(Debug)]
+pub struct ThreadToken {
+    // Not `Send`, since it represents the current thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ThreadToken {
+    /// Get the token for the current thread.
+    ///
+    /// Returns `None` if the token for the current thread has already been
+    /// taken, and not dropped yet.
+    pub fn get() -> Option<Self> {
+        if THREAD_TOKEN_TAKEN.replace(true) {
+            None
+        } else {
+            Some(Self { _not_send: PhantomData })
+        }
+    }
+
+    /// Get a [`LockToken`] for locking values of any level.
+    pub fn token(&mut self) -> LockToken<'_, Unlocked> {
+        LockToken::new()
+    }
+}
+
+impl Drop for ThreadToken {
+    fn drop(&mut self) {
+        THREAD_TOKEN_TAKEN.set(false);
+    }
+}
+
+/// Proof that the current thread holds no locks of level `L` or any level that
+/// can be locked after `L`.
+pub struct LockToken<'a, L> {
+    _borrow: PhantomData<&'a mut ()>,
+    _level: PhantomData<fn() -> L>,
+    // Not `Send`, since the proof only holds for the current thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<L> LockToken<'_, L> {
+    fn new() -> Self {
+        Self { _borrow: PhantomData, _level: PhantomData, _not_send: PhantomData }
+    }
+}
+
+impl<L> fmt::Debug for LockToken<'_, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockToken").field("level", &std::any::type_name::<L>()).finish()
+    }
+}
+
+/// A wrapper around a resource possibly shared with [`SharedReadLock`]s and
+/// [`WeakReadLock`]s, but no other `Shared`s, with lock level `L`.
+pub struct Shared<T: ?Sized, L>(crate::Shared<T>, PhantomData<fn() -> L>);
+
+impl<T, L: LockLevel> Shared<T, L> {
+    /// Create a new `Shared`.
+    pub fn new(data: T) -> Self {
+        Self(crate::Shared::new(data), PhantomData)
+    }
+
+    /// Returns the inner value, if the `Shared` has no associated
+    /// `SharedReadLock`s.
+    ///
+    /// Otherwise, an `Err` is returned with the same `Shared` that was passed
+    /// in.
+    ///
+    /// This will succeed even if there are outstanding weak references.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    pub fn unwrap(this: Self) -> Result<T, Self> {
+        crate::Shared::unwrap(this.0).map_err(|shared| Self(shared, PhantomData))
+    }
+}
+
+impl<T: ?Sized, L: LockLevel> Shared<T, L> {
+    /// Get a reference to the inner value.
+    ///
+    /// Since this never has to wait for another thread, it doesn't require a
+    /// [`LockToken`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub fn get(this: &Self) -> &T {
+        crate::Shared::get(&this.0)
+    }
+
+    /// Lock this `Shared` to be able to mutate it, blocking the current thread
+    /// until the operation succeeds.
+    ///
+    /// Returns the guard and a token for locking values with lower lock
+    /// levels while it is held.
+    #[track_caller]
+    pub fn lock<'a, H>(
+        this: &'a mut Self,
+        _token: &'a mut LockToken<'_, H>,
+    ) -> (SharedWriteGuard<'a, T>, LockToken<'a, L>)
+    where
+        L: LockAfter<H>,
+    {
+        (crate::Shared::lock(&mut this.0), LockToken::new())
+    }
+
+    /// Get a [`SharedReadLock`] for accessing the same resource read-only from
+    /// elsewhere.
+    pub fn get_read_lock(this: &Self) -> SharedReadLock<T, L> {
+        SharedReadLock(crate::Shared::get_read_lock(&this.0), PhantomData)
+    }
+
+    /// Gets the number of associated [`SharedReadLock`]s.
+    pub fn read_count(this: &Self) -> usize {
+        crate::Shared::read_count(&this.0)
+    }
+
+    /// Gets the number of associated [`WeakReadLock`]s.
+    pub fn weak_count(this: &Self) -> usize {
+        crate::Shared::weak_count(&this.0)
+    }
+}
+
+impl<T: ?Sized, L: LockLevel> ops::Deref for Shared<T, L> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        Shared::get(self)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized, L> fmt::Debug for Shared<T, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Default, L: LockLevel> Default for Shared<T, L> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A read-only reference to a resource possibly shared with up to one
+/// [`Shared`] and many [`WeakReadLock`]s, with lock level `L`.
+pub struct SharedReadLock<T: ?Sized, L>(crate::SharedReadLock<T>, PhantomData<fn() -> L>);
+
+impl<T: ?Sized, L: LockLevel> SharedReadLock<T, L> {
+    /// Lock this `SharedReadLock`, blocking the current thread until the
+    /// operation succeeds.
+    ///
+    /// Returns the guard and a token for locking values with lower lock
+    /// levels while it is held.
+    #[track_caller]
+    pub fn lock<'a, H>(
+        &'a self,
+        _token: &'a mut LockToken<'_, H>,
+    ) -> (SharedReadGuard<'a, T>, LockToken<'a, L>)
+    where
+        L: LockAfter<H>,
+    {
+        (self.0.lock(), LockToken::new())
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// If the value is currently locked for writing through the corresponding
+    /// `Shared` instance or the lock was poisoned, returns
+    /// [`TryLockError`][std::sync::TryLockError].
+    #[track_caller]
+    pub fn try_lock<'a, H>(
+        &'a self,
+        _token: &'a mut LockToken<'_, H>,
+    ) -> TryLockResult<(SharedReadGuard<'a, T>, LockToken<'a, L>)>
+    where
+        L: LockAfter<H>,
+    {
+        self.0
+            .try_lock()
+            .map(|guard| (guard, LockToken::new()))
+            .map_err(|err| crate::try_lock_error_map(err, |guard| (guard, LockToken::new())))
+    }
+
+    /// Create a new [`WeakReadLock`] pointer to this allocation.
+    pub fn downgrade(&self) -> WeakReadLock<T, L> {
+        WeakReadLock(self.0.downgrade(), PhantomData)
+    }
+
+    /// Upgrade a `SharedReadLock` to `Shared`.
+    ///
+    /// This only return `Ok(_)` if there are no other references (including a
+    /// `Shared`, or weak references) to the inner value, since otherwise it
+    /// would be possible to have multiple `Shared`s for the same inner value
+    /// alive at the same time, which would violate `Shared`s invariant of
+    /// being the only reference that is able to mutate the inner value.
+    pub fn try_upgrade(self) -> Result<Shared<T, L>, Self> {
+        match self.0.try_upgrade() {
+            Ok(shared) => Ok(Shared(shared, PhantomData)),
+            Err(read_lock) => Err(Self(read_lock, PhantomData)),
+        }
+    }
+}
+
+impl<T: ?Sized, L> Clone for SharedReadLock<T, L> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized, L> fmt::Debug for SharedReadLock<T, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A weak read-only reference to a resource possibly shared with up to one
+/// [`Shared`] and many [`SharedReadLock`]s, with lock level `L`.
+pub struct WeakReadLock<T: ?Sized, L>(crate::WeakReadLock<T>, PhantomData<fn() -> L>);
+
+impl<T: ?Sized, L: LockLevel> WeakReadLock<T, L> {
+    /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`, delaying
+    /// dropping of the inner value if successful.
+    ///
+    /// Returns `None` if the inner value has already been dropped.
+    pub fn upgrade(&self) -> Option<SharedReadLock<T, L>> {
+        self.0.upgrade().map(|read_lock| SharedReadLock(read_lock, PhantomData))
+    }
+}
+
+impl<T: ?Sized, L> Clone for WeakReadLock<T, L> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized, L> fmt::Debug for WeakReadLock<T, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
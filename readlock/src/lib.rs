@@ -1,26 +1,231 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+// Shuttle's guards are larger than std's, but it's only meant for testing.
+#![cfg_attr(shuttle, allow(clippy::result_large_err))]
+
+// Deadlock detection tracks the locks held by each OS thread, but the threads
+// of both models run on a single one.
+#[cfg(all(feature = "deadlock-detection", any(loom, shuttle)))]
+compile_error!("the `deadlock-detection` feature can't be used with `cfg(loom)` or `cfg(shuttle)`");
 
 use std::{
-    fmt, ops,
+    error::Error,
+    fmt,
+    mem::ManuallyDrop,
+    ops, ptr,
     sync::{
-        Arc, LockResult, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
-        TryLockResult, Weak,
+        atomic::{AtomicBool, Ordering},
+        Condvar, LockResult, Mutex, MutexGuard, OnceLock, PoisonError,
     },
+    thread,
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "async-blocking-check")]
+pub mod async_check;
+#[cfg(feature = "deadlock-detection")]
+pub mod deadlock;
+mod drop_hooks;
+#[cfg(feature = "hold-warnings")]
+pub mod hold_warnings;
+#[cfg(feature = "tracing")]
+mod instrument;
+mod lease;
+pub mod leveled;
 #[cfg(feature = "lite")]
 pub mod lite;
+pub mod local;
+pub mod once;
+#[cfg(feature = "reader-registry")]
+pub mod readers;
+pub mod scoped;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod static_shared;
+#[cfg(feature = "stats")]
+pub mod stats;
+mod sync;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod track;
+pub mod traits;
+mod unique;
+mod unsize;
+mod writer;
+
+use self::{
+    drop_hooks::DropHooks,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
+    track::{Acquisition, LockKind, LockState, ReaderHandle},
+    unique::{ReaderCount, ThreadWaker},
+    writer::WriterState,
+};
+
+#[doc(hidden)]
+pub use self::unsize::CoerceUnsized;
+pub use self::{
+    lease::{WriterLease, WriterSlot},
+    static_shared::StaticShared,
+};
+
+/// Implementation details shared with `readlock-tokio` and used by the macros
+/// of this crate, not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "hold-warnings")]
+    pub use crate::hold_warnings::tracker::HoldTracker;
+    #[cfg(feature = "tracing")]
+    pub use crate::instrument::Acquisition as Instrumentation;
+    pub use crate::{
+        track::{LockKind, LockState},
+        unique::ReaderCount,
+        unsize::CoerceUnsized,
+    };
+}
+
+/// A value together with the bookkeeping state shared by its `Shared`,
+/// `SharedReadLock`s and `WeakReadLock`s, all in a single allocation.
+///
+/// `repr(C)` keeps the value last, so the pointer metadata of `Inner<T>` is the
+/// same as that of `T` and unsizing coercions only change the type of the
+/// value.
+#[repr(C)]
+struct Inner<T: ?Sized> {
+    state: LockState,
+    readers: ReaderCount,
+    writer: WriterState,
+    /// Set by [`SharedReadLock::get_frozen`] once the value can't be mutated
+    /// anymore, to the offset of the value from the start of the `Inner`. Not
+    /// stored as a pointer, so it doesn't need to be updated by unsizing
+    /// coercions.
+    frozen: OnceLock<usize>,
+    /// Run after the value has been dropped or moved out.
+    drop_hooks: DropHooks,
+    /// Set once the value has been moved out of `rwlock`, by [`Shared::unwrap`]
+    /// or a conversion to an `Arc<RwLock<T>>`. The handles still using the
+    /// `Inner` afterwards have all been revoked, so they don't access the
+    /// value anymore.
+    taken: AtomicBool,
+    rwlock: ManuallyDrop<RwLock<T>>,
+}
+
+/// The generation of the pinned `SharedReadLock`, which is never revoked. Real
+/// generations don't fit into 31 bits.
+const PINNED_GENERATION: u32 = u32::MAX;
+
+impl<T> Inner<T> {
+    fn new(state: LockState, rwlock: RwLock<T>) -> Self {
+        Self {
+            state,
+            readers: ReaderCount::new(),
+            writer: WriterState::new(),
+            frozen: OnceLock::new(),
+            drop_hooks: DropHooks::new(),
+            taken: AtomicBool::new(false),
+            rwlock: ManuallyDrop::new(rwlock),
+        }
+    }
+
+    /// Move the `RwLock` out of an `Inner` that is not shared anymore. The
+    /// drop hooks run once the `Inner` is dropped.
+    fn take(mut self) -> RwLock<T> {
+        *self.taken.get_mut() = true;
+        // SAFETY: `taken` is set, so the `RwLock` is not dropped or used again.
+        unsafe { ManuallyDrop::take(&mut self.rwlock) }
+    }
+}
+
+impl<T: ?Sized> Inner<T> {
+    /// Whether read locks of the given generation have not been
+    /// [revoked][Shared::revoke_readers].
+    ///
+    /// Must be checked while holding a read lock, so the read locks can't be
+    /// revoked in the meantime.
+    fn is_current(&self, generation: u32) -> bool {
+        generation == PINNED_GENERATION || generation == self.readers.generation()
+    }
+
+    /// Format the value for a handle of the given generation, `None` for the
+    /// `Shared`.
+    fn fmt_value(&self, generation: Option<u32>, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    where
+        T: fmt::Debug,
+    {
+        if let Some(name) = self.state.name() {
+            write!(f, "{name}: ")?;
+        }
+        // Held while formatting, so the handle can't be revoked in the
+        // meantime.
+        let guard = self.rwlock.try_read();
+        match guard {
+            Err(std::sync::TryLockError::WouldBlock) => f
+                .debug_struct("RwLock")
+                .field("data", &format_args!("<locked>"))
+                .finish_non_exhaustive(),
+            _ if generation.is_some_and(|generation| !self.is_current(generation)) => {
+                f.write_str("<revoked>")
+            }
+            _ => fmt::Debug::fmt(&*self.rwlock, f),
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for Inner<T> {
+    fn drop(&mut self) {
+        // If the value has been moved out while revoked handles still used the
+        // `Inner`, the `RwLock` is leaked. It only owns resources besides the
+        // value on platforms where it is not implemented with atomics.
+        if !*self.taken.get_mut() {
+            // SAFETY: The value has not been moved out, and `rwlock` is not
+            // used again.
+            unsafe { ManuallyDrop::drop(&mut self.rwlock) };
+        }
+    }
+}
 
 /// A wrapper around a resource possibly shared with [`SharedReadLock`]s and
 /// [`WeakReadLock`]s, but no other `Shared`s.
-pub struct Shared<T: ?Sized>(Arc<RwLock<T>>);
+pub struct Shared<T: ?Sized>(Arc<Inner<T>>);
 
 #[allow(clippy::arc_with_non_send_sync)] // should not fire for generics
 impl<T> Shared<T> {
     /// Create a new `Shared`.
     pub fn new(data: T) -> Self {
-        Self(Arc::new(RwLock::new(data)))
+        Self::with_state(data, LockState::new(None))
+    }
+
+    /// Create a new `Shared` with a name.
+    ///
+    /// The name is included in the `Debug` output of the `Shared` and its
+    /// associated `SharedReadLock`s, as well as in debugging output such as
+    /// the spans emitted with the `tracing` feature.
+    ///
+    /// Only available with the `tracing` or `hold-warnings` features, since
+    /// the name is not stored otherwise.
+    #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+    pub fn with_name(data: T, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        Self::with_state(data, LockState::new(Some(name.into())))
+    }
+
+    fn with_state(data: T, state: LockState) -> Self {
+        Self(Arc::new(Inner::new(state, RwLock::new(data))))
+    }
+
+    /// Create a new `Shared` whose inner value can hold a [`WeakReadLock`] to
+    /// itself.
+    ///
+    /// `data_fn` is called with a `WeakReadLock` for the value that is being
+    /// created, which can be cloned and stored inside of it. Upgrading it
+    /// returns `None` until `new_cyclic` has returned.
+    #[track_caller]
+    pub fn new_cyclic(data_fn: impl FnOnce(&WeakReadLock<T>) -> T) -> Self {
+        let state = LockState::new(None);
+        let handle = ReaderHandle::new(&state, true);
+        Self(Arc::new_cyclic(|weak| {
+            // Read locks of a new value are of the first generation.
+            let weak = WeakReadLock(weak.clone(), 0, handle);
+            Inner::new(state, RwLock::new(data_fn(&weak)))
+        }))
     }
 
     /// Returns the inner value, if the `Shared` has no associated
@@ -29,15 +234,100 @@ impl<T> Shared<T> {
     /// Otherwise, an `Err` is returned with the same `Shared` that was passed
     /// in.
     ///
-    /// This will succeed even if there are outstanding weak references.
+    /// This will succeed even if there are outstanding weak references, or
+    /// [revoked][Self::revoke_readers] read locks.
     ///
     /// # Panics
     ///
     /// This function will panic if the lock around the inner value is poisoned.
     pub fn unwrap(this: Self) -> Result<T, Self> {
-        match Arc::try_unwrap(this.0) {
-            Ok(rwlock) => Ok(rwlock.into_inner().unwrap()),
-            Err(arc) => Err(Self(arc)),
+        if !this.0.readers.retire() {
+            return Err(this);
+        }
+        // From here on, no `SharedReadLock` for the value can be created, and
+        // the remaining ones are all revoked.
+        match Arc::try_unwrap(Self::into_arc(this)) {
+            Ok(inner) => Ok(inner.take().into_inner().unwrap()),
+            Err(inner) => {
+                let guard = inner.rwlock.write().unwrap();
+                // SAFETY: The revoked handles check their generation after
+                // acquiring a read lock, so they don't access the value
+                // anymore, and `taken` is set so it is not dropped again.
+                let data = unsafe { ptr::read(&*guard) };
+                inner.taken.store(true, Ordering::Relaxed);
+                drop(guard);
+                inner.drop_hooks.run();
+                Ok(data)
+            }
+        }
+    }
+
+    /// Returns the inner value once all associated `SharedReadLock`s have been
+    /// dropped, blocking the current thread until then.
+    ///
+    /// `WeakReadLock`s can still be upgraded while waiting, in which case the
+    /// resulting `SharedReadLock`s have to be dropped as well.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    pub fn unwrap_when_unique(this: Self) -> T {
+        match Self::wait_unique(this, None) {
+            Ok(data) => data,
+            Err(_) => unreachable!("waiting without a deadline can't time out"),
+        }
+    }
+
+    /// Like [`unwrap_when_unique`][Self::unwrap_when_unique], but gives up
+    /// after `timeout`, returning the `Shared` that was passed in.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    pub fn unwrap_when_unique_timeout(this: Self, timeout: Duration) -> Result<T, Self> {
+        Self::wait_unique(this, Instant::now().checked_add(timeout))
+    }
+
+    /// Attempt to create a `Shared` from an `Arc<RwLock<T>>`.
+    ///
+    /// This returns `Ok(_)` only if there are no further references (including
+    /// weak references) to the inner `RwLock` since otherwise, `Shared`s
+    /// invariant of being the only instance that can mutate the inner value
+    /// would be broken. The `RwLock` is moved into a new allocation.
+    pub fn try_from_inner(rwlock: Arc<RwLock<T>>) -> Result<Self, Arc<RwLock<T>>> {
+        if Arc::weak_count(&rwlock) != 0 {
+            return Err(rwlock);
+        }
+        let rwlock = Arc::try_unwrap(rwlock)?;
+        Ok(Self(Arc::new(Inner::new(LockState::new(None), rwlock))))
+    }
+
+    fn wait_unique(mut this: Self, deadline: Option<Instant>) -> Result<T, Self> {
+        let waker = ThreadWaker::current();
+        loop {
+            this.0.readers.register(&waker);
+            this = match Self::unwrap(this) {
+                Ok(data) => return Ok(data),
+                Err(this) => this,
+            };
+
+            if this.0.readers.is_zero() {
+                // A `SharedReadLock` is being created or dropped right now.
+                thread::yield_now();
+                continue;
+            }
+
+            match deadline {
+                None => thread::park(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        this.0.readers.unregister();
+                        return Err(this);
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+            }
         }
     }
 }
@@ -61,7 +351,7 @@ impl<T: ?Sized> Shared<T> {
     /// Try to get a reference to the inner value, returning an error if the
     /// lock around it is poisoned.
     pub fn try_get(this: &Self) -> LockResult<&T> {
-        match this.0.read() {
+        match this.0.rwlock.read() {
             Ok(read_guard) => Ok(unsafe { readguard_into_ref(read_guard) }),
             Err(err) => {
                 Err(poison_error_map(err, |read_guard| unsafe { readguard_into_ref(read_guard) }))
@@ -71,45 +361,134 @@ impl<T: ?Sized> Shared<T> {
 
     /// Lock this `Shared` to be able to mutate it, blocking the current thread
     /// until the operation succeeds.
+    #[track_caller]
     pub fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
-        SharedWriteGuard(this.0.write().unwrap())
+        Self::write(&this.0)
+    }
+
+    #[track_caller]
+    fn write(inner: &Inner<T>) -> SharedWriteGuard<'_, T> {
+        let mut acquisition = Acquisition::start(&inner.state, LockKind::Write, true);
+        let guard = acquisition.wait(|| inner.rwlock.write()).unwrap();
+        acquisition.acquired();
+        SharedWriteGuard(guard, acquisition)
+    }
+
+    /// Revoke all existing [`SharedReadLock`]s and [`WeakReadLock`]s for the
+    /// value.
+    ///
+    /// Afterwards, [`SharedReadLock::lock_checked`] and
+    /// [`SharedReadLock::try_lock_checked`] return an error for the revoked
+    /// read locks, and other attempts to lock them panic. Revoked
+    /// `WeakReadLock`s can't be upgraded anymore. Read locks created
+    /// afterwards through [`get_read_lock`][Self::get_read_lock] are not
+    /// affected.
+    ///
+    /// Revoked read locks are not included in
+    /// [`read_count`][Self::read_count], so [`Shared::unwrap`] succeeds even if
+    /// some of them still exist. They keep the allocation of the value alive
+    /// though, and revoked `WeakReadLock`s are included in
+    /// [`weak_count`][Self::weak_count] until they are dropped.
+    ///
+    /// This waits for the guards of the existing read locks to be dropped, like
+    /// [`lock`][Self::lock]. The read locks kept internally by a
+    /// [`StaticShared`] or [`once::OnceShared`] are not revoked, but read locks
+    /// obtained from them before are.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub fn revoke_readers(this: &mut Self) {
+        let _guard = Self::write(&this.0);
+        this.0.readers.revoke();
+        // Revoked read locks stop waiting in `writer_dropped`.
+        this.0.writer.notify();
     }
 
     /// Get a [`SharedReadLock`] for accessing the same resource read-only from
     /// elsewhere.
+    #[track_caller]
     pub fn get_read_lock(this: &Self) -> SharedReadLock<T> {
-        SharedReadLock(this.0.clone())
+        let generation = this.0.readers.acquire();
+        SharedReadLock(this.0.clone(), generation, ReaderHandle::new(&this.0.state, false))
+    }
+
+    /// Create a [`WeakReadLock`] for the value, without creating a
+    /// `SharedReadLock` first.
+    #[track_caller]
+    pub(crate) fn downgrade(this: &Self) -> WeakReadLock<T> {
+        let generation = this.0.readers.generation();
+        WeakReadLock(Arc::downgrade(&this.0), generation, ReaderHandle::new(&this.0.state, true))
     }
 
-    /// Attempt to create a `Shared` from its internal representation,
-    /// `Arc<RwLock<T>>`.
+    /// Freeze the inner value, dropping the `Shared` and returning a
+    /// [`SharedReadLock`] instead.
     ///
-    /// This returns `Ok(_)` only if there are no further references (including
-    /// weak references) to the inner `RwLock` since otherwise, `Shared`s
-    /// invariant of being the only instance that can mutate the inner value
-    /// would be broken.
-    pub fn try_from_inner(rwlock: Arc<RwLock<T>>) -> Result<Self, Arc<RwLock<T>>> {
-        if Arc::strong_count(&rwlock) == 1 && Arc::weak_count(&rwlock) == 0 {
-            Ok(Self(rwlock))
-        } else {
-            Err(rwlock)
-        }
+    /// Afterwards, the value can be read without locking through
+    /// [`SharedReadLock::get_frozen`]. Dropping the `Shared` has the same
+    /// effect.
+    #[track_caller]
+    pub fn freeze(this: Self) -> SharedReadLock<T> {
+        let read_lock = Self::get_read_lock(&this);
+        drop(this);
+        read_lock
     }
 
-    /// Turns this `Shared` into its internal representation, `Arc<RwLock<T>>`.
-    pub fn into_inner(this: Self) -> Arc<RwLock<T>> {
-        this.0
+    /// Register a function that is called once the inner value goes away.
+    ///
+    /// That is when the last `Shared` or [`SharedReadLock`] for it is dropped,
+    /// right after the value itself is dropped, when it is taken out through
+    /// [`Shared::unwrap`], or when the last of them is turned into an
+    /// `Arc<RwLock<T>>` through [`SharedReadLock::try_into_inner`]. At that
+    /// point, [`WeakReadLock`]s for the value can't be upgraded anymore.
+    ///
+    /// The function is called on the thread that drops the last reference.
+    pub fn on_drop(this: &Self, hook: impl FnOnce() + Send + 'static) {
+        this.0.drop_hooks.push(hook);
+    }
+
+    /// Set a function that is called whenever the number of associated
+    /// [`SharedReadLock`]s drops to zero, replacing any previous one.
+    ///
+    /// The function is called on the thread that drops the last
+    /// `SharedReadLock`, before that `SharedReadLock` has released its
+    /// reference to the inner value.
+    pub fn on_unique(this: &Self, hook: impl Fn() + Send + Sync + 'static) {
+        this.0.readers.set_hook(hook);
     }
 
     /// Gets the number of associated [`SharedReadLock`]s.
     pub fn read_count(this: &Self) -> usize {
-        Arc::strong_count(&this.0) - 1
+        this.0.readers.count()
     }
 
     /// Gets the number of associated [`WeakReadLock`]s.
+    ///
+    /// This includes [revoked][Self::revoke_readers] ones that have not been
+    /// dropped yet.
     pub fn weak_count(this: &Self) -> usize {
         Arc::weak_count(&this.0)
     }
+
+    /// Get a snapshot of the lock statistics of this value.
+    #[cfg(feature = "stats")]
+    pub fn stats(this: &Self) -> stats::LockStats {
+        this.0.state.stats.snapshot(Self::read_count(this), Self::weak_count(this))
+    }
+
+    /// List the [`SharedReadLock`]s and [`WeakReadLock`]s of this value that
+    /// are currently alive.
+    #[cfg(feature = "reader-registry")]
+    pub fn readers(this: &Self) -> Vec<readers::ReaderInfo> {
+        this.0.state.readers.readers()
+    }
+
+    fn into_arc(this: Self) -> Arc<Inner<T>> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { ptr::read(&this.0) }
+    }
 }
 
 /// SAFETY: Only allowed for a read guard obtained from the inner value of a
@@ -133,7 +512,7 @@ impl<T: ?Sized> ops::Deref for Shared<T> {
 
 impl<T: fmt::Debug + ?Sized> fmt::Debug for Shared<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.0.fmt_value(None, f)
     }
 }
 
@@ -143,31 +522,177 @@ impl<T: Default> Default for Shared<T> {
     }
 }
 
+impl<T: ?Sized> Drop for Shared<T> {
+    fn drop(&mut self) {
+        // Without a `Shared`, the value can't be mutated anymore, unless a
+        // `SharedReadLock` is upgraded.
+        self.0.writer.set_alive(false);
+    }
+}
+
 /// A read-only reference to a resource possibly shared with up to one
 /// [`Shared`] and many [`WeakReadLock`]s.
-pub struct SharedReadLock<T: ?Sized>(Arc<RwLock<T>>);
+pub struct SharedReadLock<T: ?Sized>(Arc<Inner<T>>, u32, ReaderHandle);
 
 impl<T: ?Sized> SharedReadLock<T> {
     /// Lock this `SharedReadLock`, blocking the current thread until the
     /// operation succeeds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is
+    /// poisoned, or this `SharedReadLock` has been
+    /// [revoked][Shared::revoke_readers].
+    #[track_caller]
     pub fn lock(&self) -> SharedReadGuard<'_, T> {
-        SharedReadGuard(self.0.read().unwrap())
+        match self.lock_checked() {
+            Ok(guard) => guard,
+            Err(RevokedError) => panic!("`SharedReadLock` was revoked"),
+        }
+    }
+
+    /// Lock this `SharedReadLock`, blocking the current thread until the
+    /// operation succeeds.
+    ///
+    /// Unlike [`lock`][Self::lock], this returns [`RevokedError`] instead of
+    /// panicking if this `SharedReadLock` has been
+    /// [revoked][Shared::revoke_readers].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub fn lock_checked(&self) -> Result<SharedReadGuard<'_, T>, RevokedError> {
+        self.read().map(Result::unwrap)
+    }
+
+    /// Like [`lock_checked`][Self::lock_checked], but returns an error instead
+    /// of panicking if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub(crate) fn read(&self) -> Result<LockResult<SharedReadGuard<'_, T>>, RevokedError> {
+        let mut acquisition =
+            Acquisition::start(&self.0.state, LockKind::Read, true).for_reader(&self.2);
+        let result = acquisition.wait(|| self.0.rwlock.read());
+        // Checked before dropping the guard, so this can't be revoked in the
+        // meantime.
+        if !self.is_current() {
+            return Err(RevokedError);
+        }
+        acquisition.acquired();
+        Ok(match result {
+            Ok(guard) => Ok(SharedReadGuard(guard, acquisition)),
+            Err(err) => Err(poison_error_map(err, |guard| SharedReadGuard(guard, acquisition))),
+        })
     }
 
     /// Try to lock this `SharedReadLock`.
     ///
     /// If the value is currently locked for writing through the corresponding
-    /// `Shared` instance or the lock was poisoned, returns [`TryLockError`].
-    pub fn try_lock(&self) -> TryLockResult<SharedReadGuard<'_, T>> {
-        self.0
-            .try_read()
-            .map(SharedReadGuard)
-            .map_err(|err| try_lock_error_map(err, SharedReadGuard))
+    /// `Shared` instance or the lock was poisoned, returns
+    /// [`TryLockError`][std::sync::TryLockError].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if this `SharedReadLock` has been
+    /// [revoked][Shared::revoke_readers].
+    #[track_caller]
+    pub fn try_lock(&self) -> std::sync::TryLockResult<SharedReadGuard<'_, T>> {
+        match self.try_lock_checked() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Err(err.into()),
+            Err(TryLockError::WouldBlock) => Err(std::sync::TryLockError::WouldBlock),
+            Err(TryLockError::Revoked) => panic!("`SharedReadLock` was revoked"),
+        }
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// Unlike [`try_lock`][Self::try_lock], this returns
+    /// [`TryLockError::Revoked`] instead of panicking if this `SharedReadLock`
+    /// has been [revoked][Shared::revoke_readers].
+    #[track_caller]
+    pub fn try_lock_checked(&self) -> TryLockResult<SharedReadGuard<'_, T>> {
+        let mut acquisition =
+            Acquisition::start(&self.0.state, LockKind::Read, false).for_reader(&self.2);
+        let result = self.0.rwlock.try_read();
+        // Checked before dropping the guard, if any, so this can't be revoked
+        // in the meantime.
+        if !self.is_current() {
+            return Err(TryLockError::Revoked);
+        }
+        match result {
+            Ok(guard) => {
+                acquisition.acquired();
+                Ok(SharedReadGuard(guard, acquisition))
+            }
+            Err(err) => Err(try_lock_error_map(err, |guard| {
+                acquisition.acquired();
+                SharedReadGuard(guard, acquisition)
+            })
+            .into()),
+        }
+    }
+
+    /// Returns `true` if the [`Shared`] for this value has not been dropped
+    /// yet, i.e. the value may still change.
+    ///
+    /// Once this returns `false`, it only returns `true` again if a
+    /// `SharedReadLock` for the value is [upgraded][Self::try_upgrade]. It
+    /// always returns `false` once this `SharedReadLock` has been
+    /// [revoked][Shared::revoke_readers].
+    pub fn is_writer_alive(&self) -> bool {
+        self.0.writer.is_alive() && self.is_current()
+    }
+
+    /// Block the current thread until the [`Shared`] for this value has been
+    /// dropped.
+    ///
+    /// Returns immediately if it was dropped already, and once this
+    /// `SharedReadLock` has been [revoked][Shared::revoke_readers].
+    pub fn writer_dropped(&self) {
+        self.0.writer.wait_dropped(|| !self.is_current());
+    }
+
+    /// Get a reference to the inner value without locking, if the [`Shared`]
+    /// for this value has been dropped or [frozen][Shared::freeze].
+    ///
+    /// Returns `None` while the `Shared` is alive, if the lock around the
+    /// inner value is poisoned or was left locked for writing by a leaked
+    /// guard, or if this `SharedReadLock` has been
+    /// [revoked][Shared::revoke_readers].
+    pub fn get_frozen(&self) -> Option<&T> {
+        // Without a `Shared`, the value can't be revoked anymore.
+        if self.0.writer.is_alive() || !self.is_current() {
+            return None;
+        }
+        let offset = match self.0.frozen.get() {
+            Some(&offset) => offset,
+            // Not blocking here, since a leaked `SharedWriteGuard` would keep
+            // the lock forever.
+            None => {
+                let guard = self.0.rwlock.try_read().ok()?;
+                let data = &*guard as *const T as *const u8 as usize;
+                let inner = Arc::as_ptr(&self.0) as *const u8 as usize;
+                *self.0.frozen.get_or_init(|| data - inner)
+            }
+        };
+        // SAFETY: The offset points to the value inside of the `Inner` kept
+        // alive by `self`, and the pointer metadata of `Inner<T>` is the same
+        // as that of `T`. The offset is only set while there is no `Shared`,
+        // and one can only be created again through `try_upgrade`, which
+        // consumes the last `SharedReadLock` and unsets it. Thus, nothing can
+        // mutate the value while `self` is borrowed.
+        Some(unsafe { &*(Arc::as_ptr(&self.0) as *const T).byte_add(offset) })
     }
 
     /// Create a new [`WeakReadLock`] pointer to this allocation.
+    #[track_caller]
     pub fn downgrade(&self) -> WeakReadLock<T> {
-        WeakReadLock(Arc::downgrade(&self.0))
+        let generation = match self.1 {
+            PINNED_GENERATION => self.0.readers.generation(),
+            generation => generation,
+        };
+        WeakReadLock(Arc::downgrade(&self.0), generation, self.2.derive(true))
     }
 
     /// Upgrade a `SharedReadLock` to `Shared`.
@@ -177,73 +702,175 @@ impl<T: ?Sized> SharedReadLock<T> {
     /// would be possible to have multiple `Shared`s for the same inner value
     /// alive at the same time, which would violate `Shared`s invariant of
     /// being the only reference that is able to mutate the inner value.
-    pub fn try_upgrade(self) -> Result<Shared<T>, Self> {
-        if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
-            Ok(Shared(self.0))
-        } else {
-            Err(self)
+    ///
+    /// A [revoked][Shared::revoke_readers] `SharedReadLock` can't be upgraded.
+    pub fn try_upgrade(mut self) -> Result<Shared<T>, Self> {
+        let Some(inner) = Arc::get_mut(&mut self.0) else { return Err(self) };
+        if self.1 != inner.readers.generation() {
+            return Err(self);
+        }
+        inner.frozen.take();
+        inner.writer.set_alive(true);
+        // The value is not unique in the sense of `on_unique`, since it is
+        // owned by the new `Shared`.
+        inner.readers.release_silently();
+        Ok(Shared(self.into_parts().0))
+    }
+
+    /// Turn this `SharedReadLock` into the pinned one for the value, which is
+    /// not revoked by [`Shared::revoke_readers`]. Used for the read locks held
+    /// by [`StaticShared`] and [`once::OnceShared`], which are not handed out
+    /// by value.
+    ///
+    /// Returns `None` if this `SharedReadLock` has been revoked.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the value has a pinned `SharedReadLock`
+    /// already.
+    pub(crate) fn pin(self) -> Option<Self> {
+        if !self.0.readers.pin(self.1) {
+            return None;
         }
+        let (inner, handle) = self.into_parts();
+        Some(Self(inner, PINNED_GENERATION, handle))
     }
 
-    /// Create a `SharedReadLock` from its internal representation,
-    /// `Arc<RwLock<T>>`.
+    /// Whether this `SharedReadLock` has not been revoked.
     ///
-    /// You can use this to create a `SharedReadLock` from a shared `RwLock`
-    /// without ever using `Shared`, if you want to expose an API where there is
-    /// a value that can be written only from inside one module or crate, but
-    /// outside users should be allowed to obtain a reusable lock for reading
-    /// the inner value.
-    pub fn from_inner(rwlock: Arc<RwLock<T>>) -> Self {
-        Self(rwlock)
+    /// Must be checked while holding a read lock, see [`Inner::is_current`].
+    fn is_current(&self) -> bool {
+        self.0.is_current(self.1)
     }
 
-    /// Attempt to turn this `SharedReadLock` into its internal representation,
-    /// `Arc<RwLock<T>>`.
+    fn into_parts(self) -> (Arc<Inner<T>>, ReaderHandle) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { (ptr::read(&this.0), ptr::read(&this.2)) }
+    }
+}
+
+impl<T> SharedReadLock<T> {
+    /// Attempt to turn this `SharedReadLock` into an `Arc<RwLock<T>>`.
     ///
     /// This returns `Ok(_)` only if there are no further references (including
     /// a `Shared`, or weak references) to the inner value, since otherwise
     /// it would be possible to have a `Shared` and an `Arc<RwLock<T>>` for
     /// the same inner value alive at the same time, which would violate
     /// `Shared`s invariant of being the only reference that is able to
-    /// mutate the inner value.
-    pub fn try_into_inner(self) -> Result<Arc<RwLock<T>>, Self> {
-        if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
-            Ok(self.0)
-        } else {
-            Err(self)
+    /// mutate the inner value. The `RwLock` is moved into a new allocation.
+    ///
+    /// A [revoked][Shared::revoke_readers] `SharedReadLock` can't be turned
+    /// into an `Arc<RwLock<T>>`.
+    pub fn try_into_inner(mut self) -> Result<Arc<RwLock<T>>, Self> {
+        let Some(inner) = Arc::get_mut(&mut self.0) else { return Err(self) };
+        if self.1 != inner.readers.generation() {
+            return Err(self);
+        }
+        inner.readers.release();
+
+        match Arc::try_unwrap(self.into_parts().0) {
+            Ok(inner) => Ok(Arc::new(inner.take())),
+            Err(_) => unreachable!("checked to be unique above"),
         }
     }
 }
 
+#[cfg(feature = "reader-registry")]
+impl<T: ?Sized> SharedReadLock<T> {
+    /// Set a label for this `SharedReadLock`, to be included in
+    /// [`Shared::readers`].
+    ///
+    /// Clones of this `SharedReadLock`, and `WeakReadLock`s created from it,
+    /// inherit the label.
+    pub fn with_label(self, label: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.2.handle.set_label(label.into());
+        self
+    }
+}
+
 impl<T: ?Sized> Clone for SharedReadLock<T> {
+    #[track_caller]
     fn clone(&self) -> Self {
-        Self(Arc::clone(&self.0))
+        let generation = match self.1 {
+            PINNED_GENERATION => self.0.readers.acquire(),
+            generation => {
+                // Clones of a revoked `SharedReadLock` are revoked as well, and
+                // not counted.
+                self.0.readers.try_acquire(generation);
+                generation
+            }
+        };
+        Self(Arc::clone(&self.0), generation, self.2.derive(false))
+    }
+}
+
+impl<T: ?Sized> Drop for SharedReadLock<T> {
+    fn drop(&mut self) {
+        match self.1 {
+            PINNED_GENERATION => self.0.readers.unpin(),
+            generation => self.0.readers.release_from(generation),
+        }
     }
 }
 
 impl<T: fmt::Debug + ?Sized> fmt::Debug for SharedReadLock<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.0.fmt_value(Some(self.1), f)
     }
 }
 
 /// A weak read-only reference to a resource possibly shared with up to one
 /// [`Shared`] and many [`SharedReadLock`]s.
-pub struct WeakReadLock<T: ?Sized>(Weak<RwLock<T>>);
+pub struct WeakReadLock<T: ?Sized>(Weak<Inner<T>>, u32, ReaderHandle);
 
 impl<T: ?Sized> WeakReadLock<T> {
     /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`, delaying
     /// dropping of the inner value if successful.
     ///
-    /// Returns `None` if the inner value has already been dropped.
+    /// Returns `None` if the inner value has already been dropped, or this
+    /// `WeakReadLock` has been [revoked][Shared::revoke_readers].
+    #[track_caller]
     pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
-        Weak::upgrade(&self.0).map(SharedReadLock)
+        let inner = Weak::upgrade(&self.0)?;
+        if !inner.readers.try_acquire(self.1) {
+            return None;
+        }
+        Some(SharedReadLock(inner, self.1, self.2.derive(false)))
+    }
+
+    /// Returns `true` if the inner value has been dropped or this
+    /// `WeakReadLock` has been [revoked][Shared::revoke_readers], i.e. it
+    /// can't be upgraded anymore.
+    pub fn is_dead(&self) -> bool {
+        Weak::upgrade(&self.0).map_or(true, |inner| inner.readers.generation() != self.1)
+    }
+
+    /// Returns `true` if both `WeakReadLock`s point to the same value.
+    ///
+    /// This also works after the value has been dropped.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "reader-registry")]
+impl<T: ?Sized> WeakReadLock<T> {
+    /// Set a label for this `WeakReadLock`, to be included in
+    /// [`Shared::readers`].
+    ///
+    /// Clones of this `WeakReadLock`, and `SharedReadLock`s created from it,
+    /// inherit the label.
+    pub fn with_label(self, label: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.2.handle.set_label(label.into());
+        self
     }
 }
 
 impl<T: ?Sized> Clone for WeakReadLock<T> {
+    #[track_caller]
     fn clone(&self) -> Self {
-        Self(Weak::clone(&self.0))
+        Self(Weak::clone(&self.0), self.1, self.2.derive(true))
     }
 }
 
@@ -256,13 +883,17 @@ impl<T: fmt::Debug + ?Sized> fmt::Debug for WeakReadLock<T> {
 /// RAII structure used to release the shared read access of a lock when
 /// dropped.
 #[clippy::has_significant_drop]
-pub struct SharedReadGuard<'a, T: ?Sized>(RwLockReadGuard<'a, T>);
+pub struct SharedReadGuard<'a, T: ?Sized>(
+    RwLockReadGuard<'a, T>,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Acquisition<'a>,
+);
 
 impl<'a, T: ?Sized + 'a> SharedReadGuard<'a, T> {
     /// Create a `SharedReadGuard` from its internal representation,
     /// `RwLockReadGuard<'a, T>`.
     pub fn from_inner(guard: RwLockReadGuard<'a, T>) -> Self {
-        Self(guard)
+        Self(guard, Acquisition::untracked())
     }
 }
 
@@ -283,13 +914,17 @@ impl<'a, T: fmt::Debug + ?Sized + 'a> fmt::Debug for SharedReadGuard<'a, T> {
 /// RAII structure used to release the exclusive write access of a lock when
 /// dropped.
 #[clippy::has_significant_drop]
-pub struct SharedWriteGuard<'a, T: ?Sized>(RwLockWriteGuard<'a, T>);
+pub struct SharedWriteGuard<'a, T: ?Sized>(
+    RwLockWriteGuard<'a, T>,
+    #[allow(dead_code)] // only used for its `Drop` implementation
+    Acquisition<'a>,
+);
 
 impl<'a, T: ?Sized> SharedWriteGuard<'a, T> {
     /// Create a `SharedWriteGuard` from its internal representation,
     /// `RwLockWriteGuard<'a, T>`.
     pub fn from_inner(guard: RwLockWriteGuard<'a, T>) -> Self {
-        Self(guard)
+        Self(guard, Acquisition::untracked())
     }
 }
 
@@ -313,14 +948,94 @@ impl<'a, T: fmt::Debug + ?Sized + 'a> fmt::Debug for SharedWriteGuard<'a, T> {
     }
 }
 
+/// Error returned from [`SharedReadLock::lock_checked`] if the
+/// `SharedReadLock` has been [revoked][Shared::revoke_readers].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevokedError;
+
+impl fmt::Display for RevokedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("read lock was revoked")
+    }
+}
+
+impl Error for RevokedError {}
+
+/// Error returned from [`SharedReadLock::try_lock_checked`].
+pub enum TryLockError<T> {
+    /// The lock around the value is poisoned.
+    Poisoned(PoisonError<T>),
+    /// The value is currently locked for writing.
+    WouldBlock,
+    /// The `SharedReadLock` has been [revoked][Shared::revoke_readers].
+    Revoked,
+}
+
+impl<T> From<std::sync::TryLockError<T>> for TryLockError<T> {
+    fn from(error: std::sync::TryLockError<T>) -> Self {
+        match error {
+            std::sync::TryLockError::Poisoned(err) => Self::Poisoned(err),
+            std::sync::TryLockError::WouldBlock => Self::WouldBlock,
+        }
+    }
+}
+
+impl<T> fmt::Display for TryLockError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Poisoned(err) => err.fmt(f),
+            Self::WouldBlock => std::sync::TryLockError::<()>::WouldBlock.fmt(f),
+            Self::Revoked => RevokedError.fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Debug for TryLockError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Poisoned(err) => f.debug_tuple("Poisoned").field(err).finish(),
+            Self::WouldBlock => f.write_str("WouldBlock"),
+            Self::Revoked => f.write_str("Revoked"),
+        }
+    }
+}
+
+impl<T> Error for TryLockError<T> {}
+
+/// A type alias for the result of [`SharedReadLock::try_lock_checked`].
+pub type TryLockResult<T> = Result<T, TryLockError<T>>;
+
 fn poison_error_map<T, U>(error: PoisonError<T>, f: impl FnOnce(T) -> U) -> PoisonError<U> {
     let inner = error.into_inner();
     PoisonError::new(f(inner))
 }
 
-fn try_lock_error_map<T, U>(error: TryLockError<T>, f: impl FnOnce(T) -> U) -> TryLockError<U> {
+fn try_lock_error_map<T, U>(
+    error: std::sync::TryLockError<T>,
+    f: impl FnOnce(T) -> U,
+) -> std::sync::TryLockError<U> {
     match error {
-        TryLockError::Poisoned(err) => TryLockError::Poisoned(poison_error_map(err, f)),
-        TryLockError::WouldBlock => TryLockError::WouldBlock,
+        std::sync::TryLockError::Poisoned(err) => {
+            std::sync::TryLockError::Poisoned(poison_error_map(err, f))
+        }
+        std::sync::TryLockError::WouldBlock => std::sync::TryLockError::WouldBlock,
     }
 }
+
+/// Lock `mutex`, ignoring poisoning.
+///
+/// Only used for the crate's internal bookkeeping, which is never left in an
+/// inconsistent state because nothing can panic while such a lock is held.
+fn lock_unpoisoned<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Block on `condvar` while `condition` returns `true`, ignoring poisoning like
+/// [`lock_unpoisoned`].
+fn wait_while_unpoisoned<'a, T>(
+    condvar: &Condvar,
+    guard: MutexGuard<'a, T>,
+    condition: impl FnMut(&mut T) -> bool,
+) -> MutexGuard<'a, T> {
+    condvar.wait_while(guard, condition).unwrap_or_else(PoisonError::into_inner)
+}
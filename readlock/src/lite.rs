@@ -1,19 +1,31 @@
-//! Versions of `Shared` and `SharedReadLock` that are implemented in terms of
-//! the [rclite] crate. Because [`rclite::Arc`] doesn't have weak references,
-//! there is no `WeakReadLock` here.
+//! Versions of `Shared`, `SharedReadLock` and `WeakReadLock` that are
+//! implemented in terms of a more compact reference-counted pointer, [`Arc`].
+//! It uses 32-bit reference counts, so every allocation has 8 bytes of
+//! overhead for reference counting instead of 16 on 64-bit platforms.
+//!
+//! Like their counterparts in the crate root, these types support unsized
+//! values, which are created through [`coerce!`][crate::coerce] or the
+//! `From<[T; N]>` implementation of [`Shared<[T]>`][Shared].
 
 use std::{
     fmt, ops,
-    sync::{LockResult, PoisonError, RwLock, TryLockResult},
+    sync::{LockResult, TryLockResult},
 };
 
-use rclite::Arc;
+use crate::{
+    __private::CoerceUnsized, poison_error_map, readguard_into_ref, sync::RwLock,
+    try_lock_error_map, SharedReadGuard, SharedWriteGuard,
+};
+
+mod arc;
+#[cfg(feature = "serde")]
+mod serde_impls;
 
-use crate::{readguard_into_ref, try_lock_error_map, SharedReadGuard, SharedWriteGuard};
+pub use self::arc::{Arc, Weak};
 
-/// A wrapper around a resource possibly shared with [`SharedReadLock`]s, but no
-/// other `Shared`s.
-pub struct Shared<T>(Arc<RwLock<T>>);
+/// A wrapper around a resource possibly shared with [`SharedReadLock`]s and
+/// [`WeakReadLock`]s, but no other `Shared`s.
+pub struct Shared<T: ?Sized>(Arc<RwLock<T>>);
 
 impl<T> Shared<T> {
     /// Create a new `Shared`.
@@ -38,7 +50,9 @@ impl<T> Shared<T> {
             Err(arc) => Err(Self(arc)),
         }
     }
+}
 
+impl<T: ?Sized> Shared<T> {
     /// Get a reference to the inner value.
     ///
     /// Usually, you don't need to call this function since `Shared<T>`
@@ -59,10 +73,8 @@ impl<T> Shared<T> {
     pub fn try_get(this: &Self) -> LockResult<&T> {
         match this.0.read() {
             Ok(read_guard) => Ok(unsafe { readguard_into_ref(read_guard) }),
-            Err(poison_err) => {
-                let read_guard = poison_err.into_inner();
-                let r = unsafe { readguard_into_ref(read_guard) };
-                Err(PoisonError::new(r))
+            Err(err) => {
+                Err(poison_error_map(err, |read_guard| unsafe { readguard_into_ref(read_guard) }))
             }
         }
     }
@@ -70,7 +82,7 @@ impl<T> Shared<T> {
     /// Lock this `Shared` to be able to mutate it, blocking the current thread
     /// until the operation succeeds.
     pub fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
-        SharedWriteGuard(this.0.write().unwrap())
+        SharedWriteGuard::from_inner(this.0.write().unwrap())
     }
 
     /// Get a [`SharedReadLock`] for accessing the same resource read-only from
@@ -78,9 +90,39 @@ impl<T> Shared<T> {
     pub fn get_read_lock(this: &Self) -> SharedReadLock<T> {
         SharedReadLock(this.0.clone())
     }
+
+    /// Attempt to create a `Shared` from its internal representation,
+    /// `Arc<RwLock<T>>`.
+    ///
+    /// This returns `Ok(_)` only if there are no further references (including
+    /// weak references) to the inner `RwLock` since otherwise, `Shared`s
+    /// invariant of being the only instance that can mutate the inner value
+    /// would be broken.
+    pub fn try_from_inner(rwlock: Arc<RwLock<T>>) -> Result<Self, Arc<RwLock<T>>> {
+        if Arc::strong_count(&rwlock) == 1 && Arc::weak_count(&rwlock) == 0 {
+            Ok(Self(rwlock))
+        } else {
+            Err(rwlock)
+        }
+    }
+
+    /// Turns this `Shared` into its internal representation, `Arc<RwLock<T>>`.
+    pub fn into_inner(this: Self) -> Arc<RwLock<T>> {
+        this.0
+    }
+
+    /// Gets the number of associated [`SharedReadLock`]s.
+    pub fn read_count(this: &Self) -> usize {
+        Arc::strong_count(&this.0) - 1
+    }
+
+    /// Gets the number of associated [`WeakReadLock`]s.
+    pub fn weak_count(this: &Self) -> usize {
+        Arc::weak_count(&this.0)
+    }
 }
 
-impl<T> ops::Deref for Shared<T> {
+impl<T: ?Sized> ops::Deref for Shared<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -88,22 +130,27 @@ impl<T> ops::Deref for Shared<T> {
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Shared<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
 /// A read-only reference to a resource possibly shared with up to one
-/// [`Shared`] and many other [`SharedReadLock`]s.
-#[derive(Clone)]
-pub struct SharedReadLock<T>(Arc<RwLock<T>>);
+/// [`Shared`] and many [`WeakReadLock`]s.
+pub struct SharedReadLock<T: ?Sized>(Arc<RwLock<T>>);
 
-impl<T> SharedReadLock<T> {
+impl<T: ?Sized> SharedReadLock<T> {
     /// Lock this `SharedReadLock`, blocking the current thread until the
     /// operation succeeds.
     pub fn lock(&self) -> SharedReadGuard<'_, T> {
-        SharedReadGuard(self.0.read().unwrap())
+        SharedReadGuard::from_inner(self.0.read().unwrap())
     }
 
     /// Try to lock this `SharedReadLock`.
@@ -111,17 +158,154 @@ impl<T> SharedReadLock<T> {
     /// If the value is currently locked for writing through the corresponding
     /// `Shared` instance or the lock was poisoned, returns [`TryLockError`].
     ///
-    /// [`TryLockError`]: crate::TryLockError
+    /// [`TryLockError`]: std::sync::TryLockError
     pub fn try_lock(&self) -> TryLockResult<SharedReadGuard<'_, T>> {
         self.0
             .try_read()
-            .map(SharedReadGuard)
-            .map_err(|err| try_lock_error_map(err, SharedReadGuard))
+            .map(SharedReadGuard::from_inner)
+            .map_err(|err| try_lock_error_map(err, SharedReadGuard::from_inner))
+    }
+
+    /// Create a new [`WeakReadLock`] pointer to this allocation.
+    pub fn downgrade(&self) -> WeakReadLock<T> {
+        WeakReadLock(Arc::downgrade(&self.0))
+    }
+
+    /// Upgrade a `SharedReadLock` to `Shared`.
+    ///
+    /// This only return `Ok(_)` if there are no other references (including a
+    /// `Shared`, or weak references) to the inner value, since otherwise it
+    /// would be possible to have multiple `Shared`s for the same inner value
+    /// alive at the same time, which would violate `Shared`s invariant of
+    /// being the only reference that is able to mutate the inner value.
+    pub fn try_upgrade(self) -> Result<Shared<T>, Self> {
+        if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
+            Ok(Shared(self.0))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Create a `SharedReadLock` from its internal representation,
+    /// `Arc<RwLock<T>>`.
+    ///
+    /// You can use this to create a `SharedReadLock` from a shared `RwLock`
+    /// without ever using `Shared`, if you want to expose an API where there is
+    /// a value that can be written only from inside one module or crate, but
+    /// outside users should be allowed to obtain a reusable lock for reading
+    /// the inner value.
+    pub fn from_inner(rwlock: Arc<RwLock<T>>) -> Self {
+        Self(rwlock)
+    }
+
+    /// Attempt to turn this `SharedReadLock` into its internal representation,
+    /// `Arc<RwLock<T>>`.
+    ///
+    /// This returns `Ok(_)` only if there are no further references (including
+    /// a `Shared`, or weak references) to the inner value, since otherwise
+    /// it would be possible to have a `Shared` and an `Arc<RwLock<T>>` for
+    /// the same inner value alive at the same time, which would violate
+    /// `Shared`s invariant of being the only reference that is able to
+    /// mutate the inner value.
+    pub fn try_into_inner(self) -> Result<Arc<RwLock<T>>, Self> {
+        if Arc::strong_count(&self.0) == 1 && Arc::weak_count(&self.0) == 0 {
+            Ok(self.0)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for SharedReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for SharedReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A weak read-only reference to a resource possibly shared with up to one
+/// [`Shared`] and many [`SharedReadLock`]s.
+pub struct WeakReadLock<T: ?Sized>(Weak<RwLock<T>>);
+
+impl<T: ?Sized> WeakReadLock<T> {
+    /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`, delaying
+    /// dropping of the inner value if successful.
+    ///
+    /// Returns `None` if the inner value has already been dropped.
+    pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
+        Weak::upgrade(&self.0).map(SharedReadLock)
+    }
+}
+
+impl<T: ?Sized> Clone for WeakReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(Weak::clone(&self.0))
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for SharedReadLock<T> {
+impl<T: ?Sized> fmt::Debug for WeakReadLock<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
+
+impl<T: ?Sized> CoerceUnsized<T> for Shared<T> {
+    type Output<U: ?Sized> = Shared<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(self, f: impl FnOnce(*const T) -> *const U) -> Shared<U> {
+        // SAFETY: Upheld by the caller.
+        Shared(unsafe { coerce_rwlock(self.0, f) })
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for SharedReadLock<T> {
+    type Output<U: ?Sized> = SharedReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> SharedReadLock<U> {
+        // SAFETY: Upheld by the caller.
+        SharedReadLock(unsafe { coerce_rwlock(self.0, f) })
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for WeakReadLock<T> {
+    type Output<U: ?Sized> = WeakReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> WeakReadLock<U> {
+        // SAFETY: Upheld by the caller.
+        WeakReadLock(unsafe { coerce_rwlock(self.0, f) })
+    }
+}
+
+/// # Safety
+///
+/// See [`CoerceUnsized::__coerce_unsized`].
+unsafe fn coerce_rwlock<P, T, U>(
+    ptr: P,
+    f: impl FnOnce(*const T) -> *const U,
+) -> P::Output<RwLock<U>>
+where
+    P: CoerceUnsized<RwLock<T>>,
+    T: ?Sized,
+    U: ?Sized,
+{
+    // The pointer metadata of `RwLock<T>` is the same as that of `T`, so these
+    // casts only change the pointee type.
+    unsafe { ptr.__coerce_unsized(|ptr| f(ptr as *const T) as *const RwLock<U>) }
+}
+
+impl<T, const N: usize> From<[T; N]> for Shared<[T]> {
+    fn from(array: [T; N]) -> Self {
+        crate::coerce!(Shared::new(array) => [T])
+    }
+}
@@ -0,0 +1,258 @@
+//! A compact atomically reference-counted pointer with support for weak
+//! references.
+//!
+//! Compared to [`std::sync::Arc`], the strong and weak counts are stored as
+//! `u32` instead of `usize`, halving the per-allocation overhead on 64-bit
+//! platforms.
+//!
+//! Unsizing coercions are supported through
+//! [`CoerceUnsized`][crate::__private::CoerceUnsized], the implementation of
+//! [`coerce!`][crate::coerce].
+
+use std::{
+    alloc::{dealloc, Layout},
+    fmt,
+    marker::PhantomData,
+    mem, ops,
+    process::abort,
+    ptr::{self, NonNull},
+    sync::atomic::{fence, AtomicU32, Ordering},
+};
+
+use crate::__private::CoerceUnsized;
+
+/// Soft limit on the number of references, leaving enough headroom that
+/// concurrent increments can not overflow before one of them aborts.
+const MAX_REFCOUNT: u32 = i32::MAX as u32;
+
+// `repr(C)` keeps `data` last, so the pointer metadata of `ArcInner<T>` is the
+// same as that of `T`.
+#[repr(C)]
+struct ArcInner<T: ?Sized> {
+    strong: AtomicU32,
+    /// The number of weak references, plus one for all strong references
+    /// together.
+    weak: AtomicU32,
+    data: T,
+}
+
+/// A thread-safe reference-counting pointer, like [`std::sync::Arc`] but with
+/// smaller reference counts.
+///
+/// This is the internal representation of [`Shared`][super::Shared] and
+/// [`SharedReadLock`][super::SharedReadLock], and only exposes the small
+/// subset of `std::sync::Arc`s API that is needed to work with them.
+pub struct Arc<T: ?Sized> {
+    ptr: NonNull<ArcInner<T>>,
+    phantom: PhantomData<ArcInner<T>>,
+}
+
+unsafe impl<T: ?Sized + Send + Sync> Send for Arc<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Arc<T> {}
+
+impl<T> Arc<T> {
+    /// Create a new `Arc`.
+    pub fn new(data: T) -> Self {
+        let inner = Box::new(ArcInner { strong: AtomicU32::new(1), weak: AtomicU32::new(1), data });
+        Self { ptr: NonNull::from(Box::leak(inner)), phantom: PhantomData }
+    }
+
+    /// Returns the inner value, if the `Arc` has exactly one strong reference.
+    ///
+    /// Otherwise, an `Err` is returned with the same `Arc` that was passed in.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if this.inner().strong.compare_exchange(1, 0, Ordering::Relaxed, Ordering::Relaxed).is_err()
+        {
+            return Err(this);
+        }
+        fence(Ordering::Acquire);
+
+        let ptr = this.ptr;
+        mem::forget(this);
+
+        // SAFETY: The strong count was one and is now zero, so nothing else
+        // can access the data anymore. The implicit weak reference held by
+        // the strong references is released afterwards, without dropping the
+        // data a second time.
+        let data = unsafe { ptr::read(&ptr.as_ref().data) };
+        drop(Weak { ptr, phantom: PhantomData });
+        Ok(data)
+    }
+}
+
+impl<T: ?Sized> Arc<T> {
+    /// Gets the number of strong references to this allocation.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.load(Ordering::Acquire) as usize
+    }
+
+    /// Gets the number of [`Weak`] references to this allocation.
+    pub fn weak_count(this: &Self) -> usize {
+        // Subtract the implicit weak reference held by the strong references.
+        this.inner().weak.load(Ordering::Acquire) as usize - 1
+    }
+
+    /// Create a new [`Weak`] pointer to this allocation.
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        increment(&this.inner().weak);
+        Weak { ptr: this.ptr, phantom: PhantomData }
+    }
+
+    fn inner(&self) -> &ArcInner<T> {
+        // SAFETY: The allocation is kept alive as long as there are strong
+        // references.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Clone for Arc<T> {
+    fn clone(&self) -> Self {
+        increment(&self.inner().strong);
+        Self { ptr: self.ptr, phantom: PhantomData }
+    }
+}
+
+impl<T: ?Sized> Drop for Arc<T> {
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        fence(Ordering::Acquire);
+
+        // SAFETY: This was the last strong reference, so nothing else can
+        // access the data anymore.
+        unsafe { ptr::drop_in_place(&mut (*self.ptr.as_ptr()).data) };
+        drop(Weak { ptr: self.ptr, phantom: PhantomData });
+    }
+}
+
+impl<T: ?Sized> ops::Deref for Arc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().data
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner().data.fmt(f)
+    }
+}
+
+/// A weak reference to the allocation of an [`Arc`], like
+/// [`std::sync::Weak`].
+pub struct Weak<T: ?Sized> {
+    ptr: NonNull<ArcInner<T>>,
+    phantom: PhantomData<ArcInner<T>>,
+}
+
+unsafe impl<T: ?Sized + Send + Sync> Send for Weak<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Weak<T> {}
+
+impl<T: ?Sized> Weak<T> {
+    /// Attempt to upgrade the `Weak` pointer to an [`Arc`].
+    ///
+    /// Returns `None` if the inner value has already been dropped.
+    pub fn upgrade(&self) -> Option<Arc<T>> {
+        let strong = &self.inner().strong;
+        let mut count = strong.load(Ordering::Relaxed);
+        loop {
+            if count == 0 {
+                return None;
+            }
+            if count > MAX_REFCOUNT {
+                abort();
+            }
+            match strong.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(Arc { ptr: self.ptr, phantom: PhantomData }),
+                Err(current) => count = current,
+            }
+        }
+    }
+
+    fn inner(&self) -> &ArcInner<T> {
+        // SAFETY: The allocation is kept alive as long as there are weak
+        // references. Only the `data` field may have been dropped already.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        increment(&self.inner().weak);
+        Self { ptr: self.ptr, phantom: PhantomData }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        if self.inner().weak.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        fence(Ordering::Acquire);
+
+        // SAFETY: There are no strong or weak references left, and the data
+        // has already been dropped by the last strong reference. Its layout
+        // only depends on the pointer metadata, not the dropped data.
+        unsafe {
+            let layout = Layout::for_value(self.ptr.as_ref());
+            dealloc(self.ptr.as_ptr().cast(), layout);
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for Arc<T> {
+    type Output<U: ?Sized> = Arc<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(self, f: impl FnOnce(*const T) -> *const U) -> Arc<U> {
+        let this = mem::ManuallyDrop::new(self);
+        // SAFETY: Upheld by the caller, see `coerce_ptr`.
+        Arc { ptr: unsafe { coerce_ptr(this.ptr, f) }, phantom: PhantomData }
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for Weak<T> {
+    type Output<U: ?Sized> = Weak<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(self, f: impl FnOnce(*const T) -> *const U) -> Weak<U> {
+        let this = mem::ManuallyDrop::new(self);
+        // SAFETY: Upheld by the caller, see `coerce_ptr`.
+        Weak { ptr: unsafe { coerce_ptr(this.ptr, f) }, phantom: PhantomData }
+    }
+}
+
+/// # Safety
+///
+/// See [`CoerceUnsized::__coerce_unsized`].
+unsafe fn coerce_ptr<T: ?Sized, U: ?Sized>(
+    ptr: NonNull<ArcInner<T>>,
+    f: impl FnOnce(*const T) -> *const U,
+) -> NonNull<ArcInner<U>> {
+    // The pointer metadata of `ArcInner<T>` is the same as that of `T`, so
+    // these casts only change the pointee type. The address is not changed by
+    // `f`, so it still points to the start of the allocation.
+    let ptr = f(ptr.as_ptr() as *const T) as *mut ArcInner<U>;
+    // SAFETY: `ptr` has the same address as the original non-null pointer.
+    unsafe { NonNull::new_unchecked(ptr) }
+}
+
+fn increment(count: &AtomicU32) {
+    // Relaxed is fine here, like for `std::sync::Arc`: new references can only
+    // be created from existing ones, which already provide the necessary
+    // synchronization.
+    if count.fetch_add(1, Ordering::Relaxed) > MAX_REFCOUNT {
+        abort();
+    }
+}
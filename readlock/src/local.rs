@@ -0,0 +1,306 @@
+//! Single-threaded versions of `Shared`, `SharedReadLock` and `WeakReadLock`
+//! that are implemented in terms of [`Rc`] and [`RefCell`].
+//!
+//! These types use non-atomic reference counts and no OS locks, which makes
+//! them a good fit for single-threaded environments like WASM or UI code.
+//! They are neither `Send` nor `Sync`.
+//!
+//! Since there are no other threads to wait for, acquiring a lock never
+//! blocks. Instead, trying to lock a `SharedReadLock` while the value is
+//! locked for writing (or the other way around) panics. Use
+//! [`SharedReadLock::try_lock`] to handle that case without panicking.
+
+use std::{
+    cell::{BorrowError, Ref, RefCell, RefMut},
+    fmt, ops,
+    rc::{Rc, Weak},
+};
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+/// A wrapper around a resource possibly shared with [`SharedReadLock`]s and
+/// [`WeakReadLock`]s, but no other `Shared`s.
+pub struct Shared<T: ?Sized>(Rc<RefCell<T>>);
+
+impl<T> Shared<T> {
+    /// Create a new `Shared`.
+    pub fn new(data: T) -> Self {
+        Self(Rc::new(RefCell::new(data)))
+    }
+
+    /// Returns the inner value, if the `Shared` has no associated
+    /// `SharedReadLock`s.
+    ///
+    /// Otherwise, an `Err` is returned with the same `Shared` that was passed
+    /// in.
+    ///
+    /// This will succeed even if there are outstanding weak references.
+    pub fn unwrap(this: Self) -> Result<T, Self> {
+        match Rc::try_unwrap(this.0) {
+            Ok(refcell) => Ok(refcell.into_inner()),
+            Err(rc) => Err(Self(rc)),
+        }
+    }
+}
+
+impl<T: ?Sized> Shared<T> {
+    /// Get a reference to the inner value.
+    ///
+    /// Usually, you don't need to call this function since `Shared<T>`
+    /// implements `Deref`. Use this if you want to pass the inner value to a
+    /// generic function where the compiler can't infer that you want to have
+    /// the `Shared` dereferenced otherwise.
+    pub fn get(this: &Self) -> &T {
+        // SAFETY: The only way to mutably borrow the inner value is through
+        // `Shared::lock`, which requires `&mut Shared`. Since the returned
+        // reference borrows `this`, no mutable borrow can be created while it
+        // exists.
+        unsafe { &*this.0.as_ptr() }
+    }
+
+    /// Lock this `Shared` to be able to mutate it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the inner value is currently locked by a
+    /// [`SharedReadLock`].
+    #[track_caller]
+    pub fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
+        match this.0.try_borrow_mut() {
+            Ok(guard) => SharedWriteGuard(guard),
+            Err(_) => panic!("can't lock `Shared` for writing while a `SharedReadGuard` exists"),
+        }
+    }
+
+    /// Get a [`SharedReadLock`] for accessing the same resource read-only from
+    /// elsewhere.
+    pub fn get_read_lock(this: &Self) -> SharedReadLock<T> {
+        SharedReadLock(this.0.clone())
+    }
+
+    /// Attempt to create a `Shared` from its internal representation,
+    /// `Rc<RefCell<T>>`.
+    ///
+    /// This returns `Ok(_)` only if there are no further references (including
+    /// weak references) to the inner `RefCell` since otherwise, `Shared`s
+    /// invariant of being the only instance that can mutate the inner value
+    /// would be broken.
+    pub fn try_from_inner(refcell: Rc<RefCell<T>>) -> Result<Self, Rc<RefCell<T>>> {
+        if Rc::strong_count(&refcell) == 1 && Rc::weak_count(&refcell) == 0 {
+            Ok(Self(refcell))
+        } else {
+            Err(refcell)
+        }
+    }
+
+    /// Turns this `Shared` into its internal representation, `Rc<RefCell<T>>`.
+    pub fn into_inner(this: Self) -> Rc<RefCell<T>> {
+        this.0
+    }
+
+    /// Gets the number of associated [`SharedReadLock`]s.
+    pub fn read_count(this: &Self) -> usize {
+        Rc::strong_count(&this.0) - 1
+    }
+
+    /// Gets the number of associated [`WeakReadLock`]s.
+    pub fn weak_count(this: &Self) -> usize {
+        Rc::weak_count(&this.0)
+    }
+}
+
+impl<T: ?Sized> ops::Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        Shared::get(self)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A read-only reference to a resource possibly shared with up to one
+/// [`Shared`] and many [`WeakReadLock`]s.
+pub struct SharedReadLock<T: ?Sized>(Rc<RefCell<T>>);
+
+impl<T: ?Sized> SharedReadLock<T> {
+    /// Lock this `SharedReadLock`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the inner value is currently locked for
+    /// writing through the corresponding `Shared` instance.
+    #[track_caller]
+    pub fn lock(&self) -> SharedReadGuard<'_, T> {
+        match self.0.try_borrow() {
+            Ok(guard) => SharedReadGuard(guard),
+            Err(_) => panic!("can't lock `SharedReadLock` while a `SharedWriteGuard` exists"),
+        }
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// If the value is currently locked for writing through the corresponding
+    /// `Shared` instance, returns [`BorrowError`].
+    pub fn try_lock(&self) -> Result<SharedReadGuard<'_, T>, BorrowError> {
+        self.0.try_borrow().map(SharedReadGuard)
+    }
+
+    /// Create a new [`WeakReadLock`] pointer to this allocation.
+    pub fn downgrade(&self) -> WeakReadLock<T> {
+        WeakReadLock(Rc::downgrade(&self.0))
+    }
+
+    /// Upgrade a `SharedReadLock` to `Shared`.
+    ///
+    /// This only return `Ok(_)` if there are no other references (including a
+    /// `Shared`, or weak references) to the inner value, since otherwise it
+    /// would be possible to have multiple `Shared`s for the same inner value
+    /// alive at the same time, which would violate `Shared`s invariant of
+    /// being the only reference that is able to mutate the inner value.
+    pub fn try_upgrade(self) -> Result<Shared<T>, Self> {
+        if Rc::strong_count(&self.0) == 1 && Rc::weak_count(&self.0) == 0 {
+            Ok(Shared(self.0))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Create a `SharedReadLock` from its internal representation,
+    /// `Rc<RefCell<T>>`.
+    ///
+    /// You can use this to create a `SharedReadLock` from a shared `RefCell`
+    /// without ever using `Shared`, if you want to expose an API where there is
+    /// a value that can be written only from inside one module or crate, but
+    /// outside users should be allowed to obtain a reusable lock for reading
+    /// the inner value.
+    pub fn from_inner(refcell: Rc<RefCell<T>>) -> Self {
+        Self(refcell)
+    }
+
+    /// Attempt to turn this `SharedReadLock` into its internal representation,
+    /// `Rc<RefCell<T>>`.
+    ///
+    /// This returns `Ok(_)` only if there are no further references (including
+    /// a `Shared`, or weak references) to the inner value, since otherwise
+    /// it would be possible to have a `Shared` and an `Rc<RefCell<T>>` for
+    /// the same inner value alive at the same time, which would violate
+    /// `Shared`s invariant of being the only reference that is able to
+    /// mutate the inner value.
+    pub fn try_into_inner(self) -> Result<Rc<RefCell<T>>, Self> {
+        if Rc::strong_count(&self.0) == 1 && Rc::weak_count(&self.0) == 0 {
+            Ok(self.0)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for SharedReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for SharedReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A weak read-only reference to a resource possibly shared with up to one
+/// [`Shared`] and many [`SharedReadLock`]s.
+pub struct WeakReadLock<T: ?Sized>(Weak<RefCell<T>>);
+
+impl<T: ?Sized> WeakReadLock<T> {
+    /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`, delaying
+    /// dropping of the inner value if successful.
+    ///
+    /// Returns `None` if the inner value has already been dropped.
+    pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
+        Weak::upgrade(&self.0).map(SharedReadLock)
+    }
+}
+
+impl<T: ?Sized> Clone for WeakReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(Weak::clone(&self.0))
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for WeakReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// RAII structure used to release the shared read access of a lock when
+/// dropped.
+#[clippy::has_significant_drop]
+pub struct SharedReadGuard<'a, T: ?Sized>(Ref<'a, T>);
+
+impl<'a, T: ?Sized + 'a> SharedReadGuard<'a, T> {
+    /// Create a `SharedReadGuard` from its internal representation,
+    /// `Ref<'a, T>`.
+    pub fn from_inner(guard: Ref<'a, T>) -> Self {
+        Self(guard)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> ops::Deref for SharedReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: fmt::Debug + ?Sized + 'a> fmt::Debug for SharedReadGuard<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// RAII structure used to release the exclusive write access of a lock when
+/// dropped.
+#[clippy::has_significant_drop]
+pub struct SharedWriteGuard<'a, T: ?Sized>(RefMut<'a, T>);
+
+impl<'a, T: ?Sized> SharedWriteGuard<'a, T> {
+    /// Create a `SharedWriteGuard` from its internal representation,
+    /// `RefMut<'a, T>`.
+    pub fn from_inner(guard: RefMut<'a, T>) -> Self {
+        Self(guard)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> ops::Deref for SharedWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: ?Sized + 'a> ops::DerefMut for SharedWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: fmt::Debug + ?Sized + 'a> fmt::Debug for SharedWriteGuard<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
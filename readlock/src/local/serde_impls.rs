@@ -0,0 +1,42 @@
+use serde::{ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Shared, SharedReadGuard, SharedReadLock, SharedWriteGuard, WeakReadLock};
+
+impl<T: Serialize + ?Sized> Serialize for Shared<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Shared::get(self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Shared::new)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedReadLock<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.try_lock().map_err(S::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for WeakReadLock<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.upgrade() {
+            Some(read_lock) => serializer.serialize_some(&read_lock),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedReadGuard<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedWriteGuard<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
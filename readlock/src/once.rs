@@ -0,0 +1,328 @@
+//! A [`Shared`] that hands out read locks before its value exists.
+//!
+//! [`OnceShared`] is created without a value. Readers obtained through
+//! [`OnceShared::get_read_lock`] block in [`SharedReadLock::lock`] until the
+//! value has been set through [`OnceShared::init`], which turns the
+//! `OnceShared` into a regular [`Shared`].
+//!
+//! ```
+//! use std::thread;
+//!
+//! use readlock::{once::OnceShared, Shared};
+//!
+//! let once = OnceShared::new();
+//! let readlock = once.get_read_lock();
+//! assert!(readlock.try_lock().is_err());
+//!
+//! let reader = thread::spawn(move || *readlock.lock());
+//! let mut shared = once.init(1);
+//! assert_eq!(reader.join().unwrap(), 1);
+//!
+//! *Shared::lock(&mut shared) = 2;
+//! ```
+//!
+//! Once initialized, all `SharedReadLock`s of this module together hold a
+//! single [`crate::SharedReadLock`] for the value, so they count as one reader
+//! in [`Shared::read_count`] and [`Shared::unwrap`] only succeeds after all of
+//! them have been dropped. That `crate::SharedReadLock` can be accessed
+//! through [`SharedReadLock::get`] to use the rest of its API, for example to
+//! get a [`crate::WeakReadLock`] for the value. It is not affected if the
+//! readers of the value are [revoked][Shared::revoke_readers], so the
+//! `SharedReadLock`s of this module keep working, but its clones are revoked.
+//!
+//! A [`WeakReadLock`] of this module can be upgraded as long as the value
+//! exists, even after all `SharedReadLock`s of this module have been dropped.
+//! In that case, it falls back to a `crate::WeakReadLock` for the value, which
+//! can't be upgraded anymore once the readers of the value are revoked.
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError, Weak},
+};
+
+use crate::{lock_unpoisoned, wait_while_unpoisoned, Shared, SharedReadGuard};
+
+/// The state shared between a `OnceShared` and its readers.
+struct Slot<T> {
+    /// The pinned `crate::SharedReadLock` for the value.
+    read_lock: OnceLock<crate::SharedReadLock<T>>,
+    /// Set once the value has been initialized, or the `OnceShared` has been
+    /// dropped without initializing it.
+    done: Mutex<bool>,
+    ready: Condvar,
+    weak: Arc<WeakSlot<T>>,
+}
+
+impl<T> Slot<T> {
+    fn done(&self) -> MutexGuard<'_, bool> {
+        lock_unpoisoned(&self.done)
+    }
+
+    fn set_done(&self) {
+        *self.done() = true;
+        self.ready.notify_all();
+    }
+
+    #[track_caller]
+    fn wait(&self) -> &crate::SharedReadLock<T> {
+        drop(wait_while_unpoisoned(&self.ready, self.done(), |done| !*done));
+        self.read_lock.get().expect("`OnceShared` was dropped without being initialized")
+    }
+}
+
+impl<T> Drop for Slot<T> {
+    fn drop(&mut self) {
+        let read_lock = self.read_lock.take();
+        if let Some(read_lock) = &read_lock {
+            lock_unpoisoned(&self.weak.state).value = Some(read_lock.downgrade());
+        }
+        // Dropped before `WeakReadLock::upgrade` can create a new `Slot`, since
+        // there can be only one pinned `crate::SharedReadLock` for the value.
+        drop(read_lock);
+
+        lock_unpoisoned(&self.weak.state).slot = None;
+        self.weak.released.notify_all();
+    }
+}
+
+/// The state shared between all `WeakReadLock`s for a value.
+struct WeakSlot<T> {
+    state: Mutex<WeakSlotState<T>>,
+    /// Notified once the `Slot` has been dropped.
+    released: Condvar,
+}
+
+struct WeakSlotState<T> {
+    /// The `Slot` used by upgraded `WeakReadLock`s, `None` after it has been
+    /// dropped.
+    slot: Option<Weak<Slot<T>>>,
+    /// A `crate::WeakReadLock` for the value to create a new `Slot` from, set
+    /// when an initialized `Slot` is dropped.
+    value: Option<crate::WeakReadLock<T>>,
+}
+
+/// The writer of a value that has not been initialized yet.
+pub struct OnceShared<T>(Arc<Slot<T>>);
+
+impl<T> OnceShared<T> {
+    /// Create a new `OnceShared`.
+    pub fn new() -> Self {
+        Self(Arc::new_cyclic(|slot| Slot {
+            read_lock: OnceLock::new(),
+            done: Mutex::new(false),
+            ready: Condvar::new(),
+            weak: Arc::new(WeakSlot {
+                state: Mutex::new(WeakSlotState { slot: Some(slot.clone()), value: None }),
+                released: Condvar::new(),
+            }),
+        }))
+    }
+
+    /// Get a [`SharedReadLock`] for reading the value once it has been
+    /// initialized.
+    pub fn get_read_lock(&self) -> SharedReadLock<T> {
+        SharedReadLock(self.0.clone())
+    }
+
+    /// Initialize the value, waking up all readers that are waiting for it.
+    ///
+    /// Returns the [`Shared`] that is used to mutate the value from now on.
+    #[track_caller]
+    pub fn init(self, value: T) -> Shared<T> {
+        let shared = Shared::new(value);
+        let read_lock = Shared::get_read_lock(&shared).pin().expect("new value was revoked");
+        if self.0.read_lock.set(read_lock).is_err() {
+            unreachable!("`OnceShared` is only initialized once");
+        }
+        // Readers are woken up when `self` is dropped.
+        shared
+    }
+}
+
+impl<T> Default for OnceShared<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OnceShared<T> {
+    fn drop(&mut self) {
+        self.0.set_done();
+    }
+}
+
+impl<T> fmt::Debug for OnceShared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceShared").finish_non_exhaustive()
+    }
+}
+
+/// A read-only reference to a value that may not have been initialized yet.
+pub struct SharedReadLock<T>(Arc<Slot<T>>);
+
+impl<T> SharedReadLock<T> {
+    /// Lock this `SharedReadLock`, blocking the current thread until the value
+    /// has been initialized and the operation succeeds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`OnceShared`] was dropped without
+    /// initializing the value, or if the lock around the value is poisoned.
+    #[track_caller]
+    pub fn lock(&self) -> SharedReadGuard<'_, T> {
+        self.0.wait().lock()
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// If the value has not been initialized yet, returns
+    /// [`TryLockError::Uninitialized`]. This is also the case if the
+    /// [`OnceShared`] was dropped without initializing it.
+    #[track_caller]
+    pub fn try_lock(&self) -> TryLockResult<SharedReadGuard<'_, T>> {
+        match self.0.read_lock.get() {
+            Some(read_lock) => Ok(read_lock.try_lock()?),
+            None => Err(TryLockError::Uninitialized),
+        }
+    }
+
+    /// Returns `true` if the value has been initialized.
+    pub fn is_initialized(&self) -> bool {
+        self.0.read_lock.get().is_some()
+    }
+
+    /// Get the [`crate::SharedReadLock`] for the value, if it has been
+    /// initialized.
+    pub fn get(&self) -> Option<&crate::SharedReadLock<T>> {
+        self.0.read_lock.get()
+    }
+
+    /// Get the [`crate::SharedReadLock`] for the value, blocking the current
+    /// thread until it has been initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`OnceShared`] was dropped without
+    /// initializing the value.
+    #[track_caller]
+    pub fn wait(&self) -> &crate::SharedReadLock<T> {
+        self.0.wait()
+    }
+
+    /// Create a new [`WeakReadLock`] pointer to this value.
+    pub fn downgrade(&self) -> WeakReadLock<T> {
+        WeakReadLock(self.0.weak.clone())
+    }
+}
+
+impl<T> Clone for SharedReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(read_lock) => read_lock.fmt(f),
+            None => f.write_str("<uninitialized>"),
+        }
+    }
+}
+
+/// A weak read-only reference to a value that may not have been initialized
+/// yet.
+///
+/// Before the value has been initialized, it can be upgraded as long as the
+/// [`OnceShared`] or any [`SharedReadLock`] of this module for the value
+/// exists. Afterwards, it can be upgraded as long as the value exists, unless
+/// all `SharedReadLock`s of this module have been dropped and the readers of
+/// the value are [revoked][Shared::revoke_readers] later.
+pub struct WeakReadLock<T>(Arc<WeakSlot<T>>);
+
+impl<T> WeakReadLock<T> {
+    /// Attempt to upgrade the `WeakReadLock` into a `SharedReadLock`.
+    ///
+    /// Returns `None` if the `OnceShared` was dropped without initializing the
+    /// value and all `SharedReadLock`s for it have been dropped, or if the
+    /// value can't be accessed anymore.
+    pub fn upgrade(&self) -> Option<SharedReadLock<T>> {
+        let mut state = lock_unpoisoned(&self.0.state);
+        while let Some(slot) = &state.slot {
+            if let Some(slot) = slot.upgrade() {
+                return Some(SharedReadLock(slot));
+            }
+            // The `Slot` is being dropped, wait for it to release its read lock.
+            state = wait_while_unpoisoned(&self.0.released, state, |state| {
+                state.slot.as_ref().is_some_and(|slot| slot.strong_count() == 0)
+            });
+        }
+
+        let read_lock = OnceLock::from(state.value.as_ref()?.upgrade()?.pin()?);
+        let slot = Arc::new(Slot {
+            read_lock,
+            done: Mutex::new(true),
+            ready: Condvar::new(),
+            weak: self.0.clone(),
+        });
+        state.slot = Some(Arc::downgrade(&slot));
+        Some(SharedReadLock(slot))
+    }
+}
+
+impl<T> Clone for WeakReadLock<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for WeakReadLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakReadLock").finish_non_exhaustive()
+    }
+}
+
+/// Error returned from [`SharedReadLock::try_lock`].
+pub enum TryLockError<T> {
+    /// The value has not been initialized.
+    Uninitialized,
+    /// The lock around the value is poisoned.
+    Poisoned(PoisonError<T>),
+    /// The value is currently locked for writing.
+    WouldBlock,
+}
+
+impl<T> From<std::sync::TryLockError<T>> for TryLockError<T> {
+    fn from(error: std::sync::TryLockError<T>) -> Self {
+        match error {
+            std::sync::TryLockError::Poisoned(err) => Self::Poisoned(err),
+            std::sync::TryLockError::WouldBlock => Self::WouldBlock,
+        }
+    }
+}
+
+impl<T> fmt::Display for TryLockError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uninitialized => f.write_str("value has not been initialized"),
+            Self::Poisoned(err) => err.fmt(f),
+            Self::WouldBlock => std::sync::TryLockError::<()>::WouldBlock.fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Debug for TryLockError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uninitialized => f.write_str("Uninitialized"),
+            Self::Poisoned(err) => f.debug_tuple("Poisoned").field(err).finish(),
+            Self::WouldBlock => f.write_str("WouldBlock"),
+        }
+    }
+}
+
+impl<T> Error for TryLockError<T> {}
+
+/// A type alias for the result of [`SharedReadLock::try_lock`].
+pub type TryLockResult<T> = Result<T, TryLockError<T>>;
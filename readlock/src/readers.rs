@@ -0,0 +1,133 @@
+//! A registry of the read handles of a value, enabled by the `reader-registry`
+//! feature.
+//!
+//! While this feature is enabled, every [`SharedReadLock`] and
+//! [`WeakReadLock`] records where and on which thread it was created, and an
+//! optional label set through [`SharedReadLock::with_label`] or
+//! [`WeakReadLock::with_label`]. [`Shared::readers`] lists the handles that
+//! are currently alive, which helps with finding out why [`Shared::unwrap`]
+//! fails.
+//!
+//! [`SharedReadLock`]: crate::SharedReadLock
+//! [`SharedReadLock::with_label`]: crate::SharedReadLock::with_label
+//! [`WeakReadLock`]: crate::WeakReadLock
+//! [`WeakReadLock::with_label`]: crate::WeakReadLock::with_label
+//! [`Shared::readers`]: crate::Shared::readers
+//! [`Shared::unwrap`]: crate::Shared::unwrap
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    panic::Location,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{self, ThreadId},
+};
+
+use crate::lock_unpoisoned;
+
+/// Information about a [`SharedReadLock`][crate::SharedReadLock] or
+/// [`WeakReadLock`][crate::WeakReadLock] returned by
+/// [`Shared::readers`][crate::Shared::readers].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ReaderInfo {
+    /// Whether this is a `WeakReadLock`, rather than a `SharedReadLock`.
+    pub weak: bool,
+    /// The location the handle was created at.
+    pub created_at: &'static Location<'static>,
+    /// The thread the handle was created on.
+    pub thread: ThreadId,
+    /// The name of the thread the handle was created on, if it has one.
+    pub thread_name: Option<String>,
+    /// The handle's label, if any.
+    pub label: Option<Cow<'static, str>>,
+    /// Whether the handle currently holds a `SharedReadGuard`.
+    pub holding_guard: bool,
+}
+
+struct Entry {
+    info: ReaderInfo,
+    guards: usize,
+}
+
+/// The registry of a single value.
+#[derive(Default)]
+pub(crate) struct Registry {
+    next_id: AtomicU64,
+    entries: Mutex<BTreeMap<u64, Entry>>,
+}
+
+impl Registry {
+    fn entries(&self) -> MutexGuard<'_, BTreeMap<u64, Entry>> {
+        lock_unpoisoned(&self.entries)
+    }
+
+    pub(crate) fn readers(&self) -> Vec<ReaderInfo> {
+        self.entries()
+            .values()
+            .map(|entry| ReaderInfo { holding_guard: entry.guards > 0, ..entry.info.clone() })
+            .collect()
+    }
+}
+
+/// The registration of a single `SharedReadLock` or `WeakReadLock`.
+pub(crate) struct Handle {
+    registry: Arc<Registry>,
+    id: u64,
+}
+
+impl Handle {
+    pub(crate) fn new(
+        registry: &Arc<Registry>,
+        weak: bool,
+        label: Option<Cow<'static, str>>,
+        location: &'static Location<'static>,
+    ) -> Self {
+        let id = registry.next_id.fetch_add(1, Ordering::Relaxed);
+        let thread = thread::current();
+        let info = ReaderInfo {
+            weak,
+            created_at: location,
+            thread: thread.id(),
+            thread_name: thread.name().map(ToOwned::to_owned),
+            label,
+            holding_guard: false,
+        };
+        registry.entries().insert(id, Entry { info, guards: 0 });
+
+        Self { registry: registry.clone(), id }
+    }
+
+    pub(crate) fn derive(&self, weak: bool, location: &'static Location<'static>) -> Self {
+        let label =
+            self.registry.entries().get(&self.id).and_then(|entry| entry.info.label.clone());
+        Self::new(&self.registry, weak, label, location)
+    }
+
+    pub(crate) fn set_label(&self, label: Cow<'static, str>) {
+        if let Some(entry) = self.registry.entries().get_mut(&self.id) {
+            entry.info.label = Some(label);
+        }
+    }
+
+    pub(crate) fn acquired(&self) {
+        if let Some(entry) = self.registry.entries().get_mut(&self.id) {
+            entry.guards += 1;
+        }
+    }
+
+    pub(crate) fn released(&self) {
+        if let Some(entry) = self.registry.entries().get_mut(&self.id) {
+            entry.guards -= 1;
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.registry.entries().remove(&self.id);
+    }
+}
@@ -0,0 +1,206 @@
+//! Versions of `Shared` and `SharedReadLock` that borrow a value from the stack
+//! instead of allocating, for use with [`std::thread::scope`].
+//!
+//! The value is stored in a [`Slot`], which is borrowed mutably by the
+//! [`Shared`] for its whole lifetime `'env`. [`SharedReadLock`]s are plain
+//! references that can be copied into scoped threads. Once `'env` has ended,
+//! the value can be taken out of the `Slot` again without waiting for any
+//! readers.
+//!
+//! ```
+//! use std::thread;
+//!
+//! use readlock::scoped::{Shared, Slot};
+//!
+//! let mut slot = Slot::new(0);
+//! let mut shared = Shared::new(&mut slot);
+//! let readlock = Shared::get_read_lock(&shared);
+//!
+//! thread::scope(|s| {
+//!     s.spawn(|| while *readlock.lock() < 3 {});
+//!     for _ in 0..3 {
+//!         *Shared::lock(&mut shared) += 1;
+//!     }
+//! });
+//!
+//! assert_eq!(slot.into_inner(), 3);
+//! ```
+
+use std::{fmt, ops, sync::TryLockResult};
+
+use crate::{
+    readguard_into_ref,
+    sync::RwLock,
+    track::{Acquisition, LockKind, LockState},
+    try_lock_error_map, SharedReadGuard, SharedWriteGuard,
+};
+
+/// Storage for a value that is shared through [`Shared`] and
+/// [`SharedReadLock`].
+pub struct Slot<T: ?Sized> {
+    state: LockState,
+    rwlock: RwLock<T>,
+}
+
+impl<T> Slot<T> {
+    /// Create a new `Slot`.
+    pub fn new(data: T) -> Self {
+        Self { state: LockState::new(None), rwlock: RwLock::new(data) }
+    }
+
+    /// Create a new `Slot` with a name.
+    ///
+    /// The name is included in the `Debug` output of the `Slot` and the
+    /// `Shared` and `SharedReadLock`s borrowing it, as well as in debugging
+    /// output such as the spans emitted with the `tracing` feature.
+    ///
+    /// Only available with the `tracing` or `hold-warnings` features, since
+    /// the name is not stored otherwise.
+    #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+    pub fn with_name(data: T, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        Self { state: LockState::new(Some(name.into())), rwlock: RwLock::new(data) }
+    }
+
+    /// Returns the inner value.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    pub fn into_inner(self) -> T {
+        self.rwlock.into_inner().unwrap()
+    }
+}
+
+impl<T: ?Sized> Slot<T> {
+    /// Get a mutable reference to the inner value.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.rwlock.get_mut().unwrap()
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Slot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.state.name() {
+            write!(f, "{name}: ")?;
+        }
+        self.rwlock.fmt(f)
+    }
+}
+
+/// A wrapper around a value borrowed from a [`Slot`], possibly shared with
+/// [`SharedReadLock`]s, but no other `Shared`s.
+pub struct Shared<'env, T: ?Sized>(&'env Slot<T>);
+
+impl<'env, T: ?Sized> Shared<'env, T> {
+    /// Create a new `Shared` for the value in `slot`.
+    pub fn new(slot: &'env mut Slot<T>) -> Self {
+        Self(slot)
+    }
+
+    /// Get a reference to the inner value.
+    ///
+    /// Usually, you don't need to call this function since `Shared<T>`
+    /// implements `Deref`. Use this if you want to pass the inner value to a
+    /// generic function where the compiler can't infer that you want to have
+    /// the `Shared` dereferenced otherwise.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub fn get(this: &Self) -> &T {
+        // SAFETY: See `crate::Shared::try_get`, only this `Shared` can mutate
+        // the value and the returned reference borrows it.
+        unsafe { readguard_into_ref(this.0.rwlock.read().unwrap()) }
+    }
+
+    /// Lock this `Shared` to be able to mutate it, blocking the current thread
+    /// until the operation succeeds.
+    #[track_caller]
+    pub fn lock(this: &mut Self) -> SharedWriteGuard<'_, T> {
+        let mut acquisition = Acquisition::start(&this.0.state, LockKind::Write, true);
+        let guard = acquisition.wait(|| this.0.rwlock.write()).unwrap();
+        acquisition.acquired();
+        SharedWriteGuard(guard, acquisition)
+    }
+
+    /// Get a [`SharedReadLock`] for accessing the same value read-only from
+    /// elsewhere.
+    pub fn get_read_lock(this: &Self) -> SharedReadLock<'env, T> {
+        SharedReadLock(this.0)
+    }
+}
+
+impl<T: ?Sized> ops::Deref for Shared<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        Shared::get(self)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Shared<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A read-only reference to a value borrowed from a [`Slot`], possibly shared
+/// with up to one [`Shared`].
+pub struct SharedReadLock<'env, T: ?Sized>(&'env Slot<T>);
+
+impl<'env, T: ?Sized> SharedReadLock<'env, T> {
+    /// Lock this `SharedReadLock`, blocking the current thread until the
+    /// operation succeeds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the lock around the inner value is poisoned.
+    #[track_caller]
+    pub fn lock(&self) -> SharedReadGuard<'env, T> {
+        let slot = self.0;
+        let mut acquisition = Acquisition::start(&slot.state, LockKind::Read, true);
+        let guard = acquisition.wait(|| slot.rwlock.read()).unwrap();
+        acquisition.acquired();
+        SharedReadGuard(guard, acquisition)
+    }
+
+    /// Try to lock this `SharedReadLock`.
+    ///
+    /// If the value is currently locked for writing through the corresponding
+    /// `Shared` instance or the lock was poisoned, returns
+    /// [`TryLockError`][std::sync::TryLockError].
+    #[track_caller]
+    pub fn try_lock(&self) -> TryLockResult<SharedReadGuard<'env, T>> {
+        let slot = self.0;
+        let mut acquisition = Acquisition::start(&slot.state, LockKind::Read, false);
+        match slot.rwlock.try_read() {
+            Ok(guard) => {
+                acquisition.acquired();
+                Ok(SharedReadGuard(guard, acquisition))
+            }
+            Err(err) => Err(try_lock_error_map(err, |guard| {
+                acquisition.acquired();
+                SharedReadGuard(guard, acquisition)
+            })),
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for SharedReadLock<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for SharedReadLock<'_, T> {}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for SharedReadLock<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
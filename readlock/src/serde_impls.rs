@@ -0,0 +1,47 @@
+use serde::{ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Shared, SharedReadGuard, SharedReadLock, SharedWriteGuard, WeakReadLock};
+
+pub(crate) const POISONED: &str = "lock poisoned";
+
+impl<T: Serialize + ?Sized> Serialize for Shared<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Shared::try_get(self).map_err(|_| S::Error::custom(POISONED))?.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Shared::new)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedReadLock<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.read()
+            .map_err(S::Error::custom)?
+            .map_err(|_| S::Error::custom(POISONED))?
+            .serialize(serializer)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for WeakReadLock<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.upgrade() {
+            Some(read_lock) => serializer.serialize_some(&read_lock),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedReadGuard<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SharedWriteGuard<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
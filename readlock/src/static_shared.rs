@@ -0,0 +1,122 @@
+//! `Shared` values in `static` items.
+
+use std::{
+    fmt,
+    sync::{Mutex, MutexGuard, OnceLock},
+};
+
+use crate::{lock_unpoisoned, Shared, SharedReadGuard, SharedReadLock};
+
+/// A value that can be stored in a `static` item, with a single writer and any
+/// number of readers.
+///
+/// The [`Shared`] for the value is created on first use. It can be taken out
+/// exactly once through [`take_writer`][Self::take_writer], while the value
+/// can be read from anywhere through [`read`][Self::read] and
+/// [`read_lock`][Self::read_lock].
+///
+/// # Examples
+///
+/// ```
+/// use readlock::{Shared, StaticShared};
+///
+/// static CONFIG: StaticShared<Vec<String>> = StaticShared::new(Vec::new());
+///
+/// let mut writer = CONFIG.take_writer().unwrap();
+/// assert!(CONFIG.take_writer().is_none());
+///
+/// Shared::lock(&mut writer).push("verbose".to_owned());
+/// assert_eq!(*CONFIG.read(), ["verbose"]);
+/// ```
+pub struct StaticShared<T> {
+    init: Mutex<Option<Init<T>>>,
+    writer: Mutex<Option<Shared<T>>>,
+    read_lock: OnceLock<SharedReadLock<T>>,
+}
+
+enum Init<T> {
+    Value(T),
+    Fn(fn() -> T),
+}
+
+impl<T> StaticShared<T> {
+    /// Create a new `StaticShared` with the given initial value.
+    pub const fn new(value: T) -> Self {
+        Self::with_init(Init::Value(value))
+    }
+
+    /// Create a new `StaticShared` whose initial value is computed by `init`
+    /// on first use.
+    pub const fn new_lazy(init: fn() -> T) -> Self {
+        Self::with_init(Init::Fn(init))
+    }
+
+    const fn with_init(init: Init<T>) -> Self {
+        Self { init: Mutex::new(Some(init)), writer: Mutex::new(None), read_lock: OnceLock::new() }
+    }
+
+    /// Take the [`Shared`] for the value.
+    ///
+    /// Returns `None` if it has been taken before.
+    ///
+    /// Once the `Shared` is dropped, the value can't change anymore, but can
+    /// still be read.
+    pub fn take_writer(&self) -> Option<Shared<T>> {
+        self.read_lock();
+        lock_unpoisoned(&self.writer).take()
+    }
+
+    /// Get the [`SharedReadLock`] for the value.
+    ///
+    /// It is not affected if the readers of the value are
+    /// [revoked][Shared::revoke_readers], but its clones are.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if computing the initial value panicked
+    /// before.
+    pub fn read_lock(&self) -> &SharedReadLock<T> {
+        self.read_lock.get_or_init(|| {
+            let init = self.init().take().expect("initializing `StaticShared` panicked");
+            let data = match init {
+                Init::Value(data) => data,
+                Init::Fn(f) => f(),
+            };
+
+            let shared = Shared::new(data);
+            let read_lock = Shared::get_read_lock(&shared).pin().expect("new value was revoked");
+            *lock_unpoisoned(&self.writer) = Some(shared);
+            read_lock
+        })
+    }
+
+    /// Lock the value for reading, blocking the current thread until the
+    /// operation succeeds.
+    ///
+    /// For a `StaticShared` in a `static` item, the returned guard has a
+    /// `'static` lifetime. Like [`read_lock`][Self::read_lock], this keeps
+    /// working if the readers of the value are
+    /// [revoked][Shared::revoke_readers].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if computing the initial value panicked
+    /// before, or if the lock around the value is poisoned.
+    #[track_caller]
+    pub fn read(&self) -> SharedReadGuard<'_, T> {
+        self.read_lock().lock()
+    }
+
+    fn init(&self) -> MutexGuard<'_, Option<Init<T>>> {
+        lock_unpoisoned(&self.init)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StaticShared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.read_lock.get() {
+            Some(read_lock) => read_lock.fmt(f),
+            None => f.write_str("<uninitialized>"),
+        }
+    }
+}
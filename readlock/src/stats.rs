@@ -0,0 +1,229 @@
+//! Lock contention statistics, enabled by the `stats` feature.
+//!
+//! While this feature is enabled, every acquisition of a [`SharedReadGuard`]
+//! or [`SharedWriteGuard`] through `lock` or `try_lock` is recorded, including
+//! how long it took to acquire the lock and how long the guard was held.
+//! [`Shared::stats`] returns a snapshot of these statistics, which can be
+//! exported through the [`metrics`](https://docs.rs/metrics) facade using
+//! [`LockStats::record_metrics`] if the `metrics` feature is enabled too.
+//!
+//! [`SharedReadGuard`]: crate::SharedReadGuard
+//! [`SharedWriteGuard`]: crate::SharedWriteGuard
+//! [`Shared::stats`]: crate::Shared::stats
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::track::LockKind;
+
+/// A snapshot of the statistics of a `Shared` value.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct LockStats {
+    /// Statistics for read locks, taken through `SharedReadLock`.
+    pub read: AccessStats,
+    /// Statistics for write locks, taken through `Shared`.
+    pub write: AccessStats,
+    /// Whether any `SharedReadGuard`s were alive at the time of the snapshot.
+    pub readers_holding: bool,
+    /// Whether the `Shared` was waiting to lock the value for writing at the
+    /// time of the snapshot.
+    pub writer_waiting: bool,
+    /// The number of associated `SharedReadLock`s.
+    pub read_count: usize,
+    /// The number of associated `WeakReadLock`s.
+    pub weak_count: usize,
+}
+
+/// Statistics for one kind of lock acquisition.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct AccessStats {
+    /// The number of times the lock was acquired.
+    pub acquisitions: u64,
+    /// The number of acquisitions that had to wait for the lock to be
+    /// released by a conflicting guard first.
+    pub contended: u64,
+    /// The total time spent waiting to acquire the lock.
+    pub total_wait: Duration,
+    /// The longest time spent waiting to acquire the lock.
+    pub max_wait: Duration,
+    /// The total time the lock was held, not including guards that are still
+    /// alive.
+    pub total_hold: Duration,
+    /// The longest time the lock was held, not including guards that are
+    /// still alive.
+    pub max_hold: Duration,
+}
+
+#[cfg(feature = "metrics")]
+impl LockStats {
+    /// Record these statistics through the `metrics` facade.
+    ///
+    /// All metrics are labeled with `lock = name`, and per-kind metrics
+    /// additionally with `kind = "read"` or `kind = "write"`. Since the
+    /// statistics are cumulative, counters are set to their absolute values,
+    /// so this can be called periodically for the same value.
+    pub fn record_metrics(&self, name: impl Into<metrics::SharedString>) {
+        let name = name.into();
+        for (kind, stats) in [("read", &self.read), ("write", &self.write)] {
+            let labels = [("lock", name.clone()), ("kind", kind.into())];
+            metrics::counter!("readlock_acquisitions_total", &labels).absolute(stats.acquisitions);
+            metrics::counter!("readlock_contended_acquisitions_total", &labels)
+                .absolute(stats.contended);
+            metrics::gauge!("readlock_wait_seconds_total", &labels)
+                .set(stats.total_wait.as_secs_f64());
+            metrics::gauge!("readlock_wait_seconds_max", &labels).set(stats.max_wait.as_secs_f64());
+            metrics::gauge!("readlock_hold_seconds_total", &labels)
+                .set(stats.total_hold.as_secs_f64());
+            metrics::gauge!("readlock_hold_seconds_max", &labels).set(stats.max_hold.as_secs_f64());
+        }
+
+        let labels = [("lock", name)];
+        metrics::gauge!("readlock_readers_holding", &labels).set(f64::from(self.readers_holding));
+        metrics::gauge!("readlock_writer_waiting", &labels).set(f64::from(self.writer_waiting));
+        metrics::gauge!("readlock_read_count", &labels).set(self.read_count as f64);
+        metrics::gauge!("readlock_weak_count", &labels).set(self.weak_count as f64);
+    }
+}
+
+/// Per-value statistics.
+#[derive(Default)]
+pub(crate) struct State {
+    read: AccessState,
+    write: AccessState,
+    readers_holding: AtomicUsize,
+    writer_waiting: AtomicBool,
+    writer_holding: AtomicBool,
+}
+
+impl State {
+    pub(crate) fn snapshot(&self, read_count: usize, weak_count: usize) -> LockStats {
+        LockStats {
+            read: self.read.snapshot(),
+            write: self.write.snapshot(),
+            readers_holding: self.readers_holding.load(Ordering::Relaxed) > 0,
+            writer_waiting: self.writer_waiting.load(Ordering::Relaxed),
+            read_count,
+            weak_count,
+        }
+    }
+
+    fn access(&self, kind: LockKind) -> &AccessState {
+        match kind {
+            LockKind::Read => &self.read,
+            LockKind::Write => &self.write,
+        }
+    }
+}
+
+#[derive(Default)]
+struct AccessState {
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    total_wait_nanos: AtomicU64,
+    max_wait_nanos: AtomicU64,
+    total_hold_nanos: AtomicU64,
+    max_hold_nanos: AtomicU64,
+}
+
+impl AccessState {
+    fn snapshot(&self) -> AccessStats {
+        let load = |value: &AtomicU64| value.load(Ordering::Relaxed);
+        let load_duration = |nanos: &AtomicU64| Duration::from_nanos(load(nanos));
+
+        AccessStats {
+            acquisitions: load(&self.acquisitions),
+            contended: load(&self.contended),
+            total_wait: load_duration(&self.total_wait_nanos),
+            max_wait: load_duration(&self.max_wait_nanos),
+            total_hold: load_duration(&self.total_hold_nanos),
+            max_hold: load_duration(&self.max_hold_nanos),
+        }
+    }
+}
+
+fn record_duration(total: &AtomicU64, max: &AtomicU64, duration: Duration) {
+    let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+    total.fetch_add(nanos, Ordering::Relaxed);
+    max.fetch_max(nanos, Ordering::Relaxed);
+}
+
+/// Statistics bookkeeping for a single lock acquisition.
+pub(crate) struct Acquisition<'a> {
+    state: &'a State,
+    kind: LockKind,
+    contended: bool,
+    acquired: bool,
+    /// The time the acquisition was started, until the lock is acquired. Then
+    /// the time it was acquired.
+    since: Instant,
+}
+
+impl<'a> Acquisition<'a> {
+    pub(crate) fn start(state: &'a State, kind: LockKind, blocking: bool) -> Self {
+        // Only the `Shared` can lock the value for writing, so a read lock is
+        // contended if the writer holds or waits for the lock (`RwLock` may
+        // prefer writers), and a write lock is contended if any reader holds
+        // it.
+        let contended = match kind {
+            LockKind::Read => {
+                state.writer_holding.load(Ordering::Relaxed)
+                    || state.writer_waiting.load(Ordering::Relaxed)
+            }
+            LockKind::Write => state.readers_holding.load(Ordering::Relaxed) > 0,
+        };
+        if kind == LockKind::Write && blocking {
+            state.writer_waiting.store(true, Ordering::Relaxed);
+        }
+
+        Self { state, kind, contended, acquired: false, since: Instant::now() }
+    }
+
+    pub(crate) fn acquired(&mut self) {
+        let now = Instant::now();
+        let access = self.state.access(self.kind);
+        access.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if self.contended {
+            access.contended.fetch_add(1, Ordering::Relaxed);
+        }
+        record_duration(&access.total_wait_nanos, &access.max_wait_nanos, now - self.since);
+
+        match self.kind {
+            LockKind::Read => {
+                self.state.readers_holding.fetch_add(1, Ordering::Relaxed);
+            }
+            LockKind::Write => {
+                self.state.writer_waiting.store(false, Ordering::Relaxed);
+                self.state.writer_holding.store(true, Ordering::Relaxed);
+            }
+        }
+        self.acquired = true;
+        self.since = now;
+    }
+
+    pub(crate) fn release(&self) {
+        if !self.acquired {
+            // Waiting for the lock was aborted, for example by a panic because
+            // it is poisoned.
+            if self.kind == LockKind::Write {
+                self.state.writer_waiting.store(false, Ordering::Relaxed);
+            }
+            return;
+        }
+
+        let access = self.state.access(self.kind);
+        record_duration(&access.total_hold_nanos, &access.max_hold_nanos, self.since.elapsed());
+
+        match self.kind {
+            LockKind::Read => {
+                self.state.readers_holding.fetch_sub(1, Ordering::Relaxed);
+            }
+            LockKind::Write => {
+                self.state.writer_holding.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! The synchronization primitives used by `Shared`, `SharedReadLock` and
+//! `WeakReadLock`.
+//!
+//! These are the ones from `std`, unless model checking with [loom] or
+//! [Shuttle] is enabled through `cfg(loom)` or `cfg(shuttle)`, in which case
+//! they are replaced with model-checked versions.
+//!
+//! Neither of them has an `Arc` that supports `Weak` and unsized values, so
+//! with either of them, `std`'s `Arc` and `Weak` are wrapped to make their
+//! reference count updates interleaving points of the model. This covers the
+//! reference counts checked by `try_upgrade` and the `*_inner` conversions.
+//!
+//! [loom]: https://docs.rs/loom
+//! [Shuttle]: https://docs.rs/shuttle
+
+#[cfg(loom)]
+mod loom_rwlock;
+#[cfg(any(loom, shuttle))]
+mod model_arc;
+
+#[cfg(loom)]
+pub(crate) use self::loom_rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(any(loom, shuttle))]
+pub(crate) use self::model_arc::{Arc, Weak};
+#[cfg(all(shuttle, not(loom)))]
+pub(crate) use shuttle::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(not(any(loom, shuttle)))]
+pub(crate) use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
@@ -0,0 +1,166 @@
+//! A wrapper around loom's `RwLock` that supports unsized values.
+//!
+//! Poisoning is tracked here like `std` does it, since loom's `RwLock` never
+//! reports it.
+//!
+//! The types in here are `pub` since they are used in the signatures of public
+//! conversion functions, but they are not reachable from outside the crate.
+#![allow(unreachable_pub)]
+
+use std::{
+    cell::UnsafeCell,
+    fmt, ops,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LockResult, PoisonError, TryLockError, TryLockResult,
+    },
+    thread,
+};
+
+/// A reader-writer lock, model-checked by loom.
+pub struct RwLock<T: ?Sized> {
+    lock: loom::sync::RwLock<()>,
+    /// Set when a thread panics while holding the write lock. Only accessed
+    /// while holding `lock`, so it doesn't need to be model-checked itself.
+    poisoned: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+// SAFETY: Same as for `std::sync::RwLock`, access to `data` is synchronized
+// through `lock`.
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            lock: loom::sync::RwLock::new(()),
+            poisoned: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn into_inner(self) -> LockResult<T> {
+        poison_result(self.poisoned.into_inner(), self.data.into_inner())
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> {
+        let guard = self.lock.read().unwrap_or_else(PoisonError::into_inner);
+        // SAFETY: There are only readers while the read lock is held.
+        let guard = RwLockReadGuard { _guard: guard, data: unsafe { &*self.data.get() } };
+        poison_result(self.is_poisoned(), guard)
+    }
+
+    pub fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        let guard = match self.lock.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return Err(TryLockError::WouldBlock),
+        };
+        // SAFETY: There are only readers while the read lock is held.
+        let guard = RwLockReadGuard { _guard: guard, data: unsafe { &*self.data.get() } };
+        poison_result(self.is_poisoned(), guard).map_err(TryLockError::from)
+    }
+
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        let poisoned = *self.poisoned.get_mut();
+        poison_result(poisoned, self.data.get_mut())
+    }
+
+    pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>> {
+        let guard = self.lock.write().unwrap_or_else(PoisonError::into_inner);
+        let guard = RwLockWriteGuard {
+            _guard: guard,
+            poisoned: &self.poisoned,
+            panicking: thread::panicking(),
+            // SAFETY: The write lock guarantees exclusive access.
+            data: unsafe { &mut *self.data.get() },
+        };
+        poison_result(self.is_poisoned(), guard)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+}
+
+fn poison_result<T>(poisoned: bool, value: T) -> LockResult<T> {
+    if poisoned {
+        Err(PoisonError::new(value))
+    } else {
+        Ok(value)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+        match self.try_read() {
+            Ok(guard) => d.field("data", &&*guard),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+/// RAII structure used to release the shared read access of a lock when
+/// dropped.
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    _guard: loom::sync::RwLockReadGuard<'a, ()>,
+    data: &'a T,
+}
+
+impl<T: ?Sized> ops::Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.fmt(f)
+    }
+}
+
+/// RAII structure used to release the exclusive write access of a lock when
+/// dropped.
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    _guard: loom::sync::RwLockWriteGuard<'a, ()>,
+    poisoned: &'a AtomicBool,
+    /// Whether the thread was panicking already when the lock was acquired.
+    panicking: bool,
+    data: &'a mut T,
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        // Runs before `_guard` is dropped, so the lock is still held.
+        if !self.panicking && thread::panicking() {
+            self.poisoned.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T: ?Sized> ops::Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T: ?Sized> ops::DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.data
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.fmt(f)
+    }
+}
@@ -0,0 +1,173 @@
+//! Wrappers around `std`'s `Arc` and `Weak` for model checking.
+//!
+//! Neither loom nor Shuttle have an `Arc` with support for `Weak` and unsized
+//! values, so these wrap the ones from `std` and add an interleaving point of
+//! the model before every operation that reads or updates the reference
+//! counts. Each of those operations is atomic, so this lets the model explore
+//! all orders in which they can happen.
+#![allow(unreachable_pub)]
+
+use std::{fmt, mem::ManuallyDrop, ops, ptr};
+
+/// An interleaving point of the model, without synchronizing with other
+/// threads.
+fn yield_point() {
+    #[cfg(loom)]
+    {
+        loom::lazy_static! {
+            static ref POINT: loom::sync::atomic::AtomicUsize =
+                loom::sync::atomic::AtomicUsize::new(0);
+        }
+        POINT.load(std::sync::atomic::Ordering::Relaxed);
+    }
+    #[cfg(not(loom))]
+    {
+        static POINT: shuttle::sync::atomic::AtomicUsize =
+            shuttle::sync::atomic::AtomicUsize::new(0);
+        POINT.load(std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// A [`std::sync::Arc`] whose reference count updates are interleaving points
+/// of the model.
+pub struct Arc<T: ?Sized>(std::sync::Arc<T>);
+
+impl<T> Arc<T> {
+    pub fn new(data: T) -> Self {
+        Self(std::sync::Arc::new(data))
+    }
+
+    pub fn new_cyclic(data_fn: impl FnOnce(&Weak<T>) -> T) -> Self {
+        Self(std::sync::Arc::new_cyclic(|weak| data_fn(&Weak(weak.clone()))))
+    }
+
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        yield_point();
+        std::sync::Arc::try_unwrap(this.into_std()).map_err(Self)
+    }
+}
+
+impl<T: ?Sized> Arc<T> {
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        yield_point();
+        std::sync::Arc::get_mut(&mut this.0)
+    }
+
+    pub fn strong_count(this: &Self) -> usize {
+        yield_point();
+        std::sync::Arc::strong_count(&this.0)
+    }
+
+    pub fn weak_count(this: &Self) -> usize {
+        yield_point();
+        std::sync::Arc::weak_count(&this.0)
+    }
+
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        yield_point();
+        Weak(std::sync::Arc::downgrade(&this.0))
+    }
+
+    pub fn as_ptr(this: &Self) -> *const T {
+        std::sync::Arc::as_ptr(&this.0)
+    }
+
+    pub fn into_raw(this: Self) -> *const T {
+        std::sync::Arc::into_raw(this.into_std())
+    }
+
+    /// # Safety
+    ///
+    /// See [`std::sync::Arc::from_raw`].
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        // SAFETY: Upheld by the caller.
+        Self(unsafe { std::sync::Arc::from_raw(ptr) })
+    }
+
+    fn into_std(self) -> std::sync::Arc<T> {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { ptr::read(&this.0) }
+    }
+}
+
+impl<T: ?Sized> Clone for Arc<T> {
+    fn clone(&self) -> Self {
+        yield_point();
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Drop for Arc<T> {
+    fn drop(&mut self) {
+        yield_point();
+    }
+}
+
+impl<T: ?Sized> ops::Deref for Arc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for Arc<T> {
+    fn from(value: Box<T>) -> Self {
+        Self(std::sync::Arc::from(value))
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A [`std::sync::Weak`] whose reference count updates are interleaving
+/// points of the model.
+pub struct Weak<T: ?Sized>(std::sync::Weak<T>);
+
+impl<T: ?Sized> Weak<T> {
+    pub fn upgrade(&self) -> Option<Arc<T>> {
+        yield_point();
+        self.0.upgrade().map(Arc)
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+
+    pub fn into_raw(self) -> *const T {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        unsafe { ptr::read(&this.0) }.into_raw()
+    }
+
+    /// # Safety
+    ///
+    /// See [`std::sync::Weak::from_raw`].
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        // SAFETY: Upheld by the caller.
+        Self(unsafe { std::sync::Weak::from_raw(ptr) })
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        yield_point();
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        yield_point();
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
@@ -0,0 +1,200 @@
+//! Utilities for testing code that uses `Shared` and its read locks, enabled
+//! by the `test-util` feature.
+//!
+//! These allow deterministically reaching error paths and interleavings that
+//! are otherwise only reached through timing, like [`TryLockError`]s and
+//! [`PoisonError`]s.
+//!
+//! This is not meant to be enabled outside of tests.
+//!
+//! [`TryLockError`]: std::sync::TryLockError
+//! [`PoisonError`]: std::sync::PoisonError
+
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::Shared;
+
+/// Poison the lock around the inner value of `this`.
+///
+/// Afterwards, [`Shared::try_get`] and [`SharedReadLock::try_lock`] return an
+/// error, and [`Shared::get`] and [`SharedReadLock::lock`] panic.
+///
+/// The lock is poisoned by unwinding while holding a write guard, so this
+/// does nothing if panics abort. The panic hook is not invoked.
+///
+/// [`SharedReadLock::lock`]: crate::SharedReadLock::lock
+/// [`SharedReadLock::try_lock`]: crate::SharedReadLock::try_lock
+pub fn poison<T: ?Sized>(this: &mut Shared<T>) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = this.0.rwlock.write();
+        panic::resume_unwind(Box::new("readlock::test_util::poison"));
+    }));
+}
+
+/// Acquire the write lock of `this` on a background thread, and hold it until
+/// the returned [`HeldWriteLock`] is dropped.
+///
+/// When this function returns, the lock is held, so
+/// [`SharedReadLock::try_lock`] returns [`TryLockError::WouldBlock`] and
+/// [`SharedReadLock::lock`] blocks until it is released.
+///
+/// [`SharedReadLock::lock`]: crate::SharedReadLock::lock
+/// [`SharedReadLock::try_lock`]: crate::SharedReadLock::try_lock
+/// [`TryLockError::WouldBlock`]: std::sync::TryLockError::WouldBlock
+pub fn hold_write_lock<T>(this: &mut Shared<T>) -> HeldWriteLock<'_, T>
+where
+    T: ?Sized + Send + Sync + 'static,
+{
+    let inner = this.0.clone();
+    let (locked_tx, locked_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let thread = thread::spawn(move || {
+        let _guard = inner.rwlock.write();
+        locked_tx.send(()).unwrap();
+        // Returns an error once the sender is dropped.
+        let _ = release_rx.recv();
+    });
+    locked_rx.recv().expect("write lock thread panicked");
+
+    HeldWriteLock { release: Some(release_tx), thread: Some(thread), _shared: PhantomData }
+}
+
+/// A write lock held by a background thread, returned by [`hold_write_lock`].
+///
+/// The lock is released when this is dropped.
+#[must_use = "the write lock is released immediately if this is dropped"]
+pub struct HeldWriteLock<'a, T: ?Sized> {
+    release: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _shared: PhantomData<&'a mut Shared<T>>,
+}
+
+impl<T: ?Sized> HeldWriteLock<'_, T> {
+    /// Release the write lock, waiting until the background thread has done so.
+    ///
+    /// This is the same as dropping the `HeldWriteLock`.
+    pub fn release(self) {}
+}
+
+impl<T: ?Sized> Drop for HeldWriteLock<'_, T> {
+    fn drop(&mut self) {
+        drop(self.release.take());
+        if let Some(thread) = self.thread.take() {
+            // The thread can't panic, unless the lock guard's destructor does.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for HeldWriteLock<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeldWriteLock").finish_non_exhaustive()
+    }
+}
+
+static MAX_DELAY_NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// Delay every lock acquisition by a random duration of up to `max`.
+///
+/// This applies to all `Shared`s and read locks, on all threads, and makes
+/// races between threads that acquire locks more likely to show up. Pass
+/// [`Duration::ZERO`] to disable the delays again, which is the default.
+pub fn set_random_delays(max: Duration) {
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    MAX_DELAY_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Sleep for a random duration if [`set_random_delays`] was called.
+pub(crate) fn delay() {
+    let max = MAX_DELAY_NANOS.load(Ordering::Relaxed);
+    if max != 0 {
+        thread::sleep(Duration::from_nanos(random() % (max + 1)));
+    }
+}
+
+/// A xorshift pseudo-random number generator with a random seed per thread.
+fn random() -> u64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(RandomState::new().build_hasher().finish() | 1);
+    }
+
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        x
+    })
+}
+
+/// Move `shared` to a background thread, where it is dropped once
+/// [`WriterDrop::drop_writer`] is called.
+///
+/// This allows tests to decide exactly when the readers of a value lose their
+/// writer, even if the `Shared` would otherwise have to be moved into a
+/// different part of the code under test.
+pub fn defer_writer_drop<T>(shared: Shared<T>) -> WriterDrop
+where
+    T: ?Sized + Send + Sync + 'static,
+{
+    let (drop_tx, drop_rx) = mpsc::channel::<()>();
+    let thread = thread::spawn(move || {
+        // Returns an error once the sender is dropped.
+        let _ = drop_rx.recv();
+        drop(shared);
+    });
+
+    WriterDrop { trigger: Some(drop_tx), thread: Some(thread) }
+}
+
+/// A `Shared` waiting to be dropped, returned by [`defer_writer_drop`].
+///
+/// If this is dropped without calling [`drop_writer`][Self::drop_writer], the
+/// `Shared` is dropped as well.
+pub struct WriterDrop {
+    trigger: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WriterDrop {
+    /// Drop the `Shared`, waiting until its destructor has finished.
+    ///
+    /// Afterwards, upgrading a [`SharedReadLock`] that is the last reference
+    /// to the value succeeds.
+    ///
+    /// [`SharedReadLock`]: crate::SharedReadLock
+    pub fn drop_writer(self) {}
+}
+
+impl Drop for WriterDrop {
+    fn drop(&mut self) {
+        drop(self.trigger.take());
+        if let Some(thread) = self.thread.take() {
+            if let Err(panic) = thread.join() {
+                if !thread::panicking() {
+                    panic::resume_unwind(panic);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for WriterDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriterDrop").finish_non_exhaustive()
+    }
+}
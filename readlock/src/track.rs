@@ -0,0 +1,267 @@
+//! Bookkeeping for lock acquisitions, used by the debugging features.
+//!
+//! When none of those features is enabled, the types in here are zero-sized
+//! and their methods do nothing. The state needed regardless of the enabled
+//! features, like the number of readers, is kept next to the value instead.
+
+use std::{borrow::Cow, fmt, marker::PhantomData, panic::Location};
+
+#[cfg(feature = "async-blocking-check")]
+use crate::async_check;
+#[cfg(feature = "deadlock-detection")]
+use crate::deadlock;
+#[cfg(feature = "hold-warnings")]
+use crate::hold_warnings::tracker::HoldTracker;
+#[cfg(feature = "tracing")]
+use crate::instrument;
+#[cfg(feature = "reader-registry")]
+use crate::readers;
+#[cfg(feature = "stats")]
+use crate::stats;
+#[cfg(feature = "test-util")]
+use crate::test_util;
+
+/// Whether a lock is acquired for reading or for writing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockKind {
+    /// Shared read access.
+    Read,
+    /// Exclusive write access.
+    Write,
+}
+
+impl LockKind {
+    #[cfg(feature = "tracing")]
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Per-value state, stored next to the `RwLock`. Also used by
+/// `readlock-tokio`.
+pub struct LockState {
+    #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+    name: Option<Cow<'static, str>>,
+    #[cfg(feature = "deadlock-detection")]
+    id: deadlock::LockId,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: stats::State,
+    #[cfg(feature = "reader-registry")]
+    pub(crate) readers: std::sync::Arc<readers::Registry>,
+}
+
+impl LockState {
+    #[allow(unused_variables)] // unused if no feature using the name is enabled
+    /// Create the state for a value with the given name.
+    pub fn new(name: Option<Cow<'static, str>>) -> Self {
+        Self {
+            #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+            name,
+            #[cfg(feature = "deadlock-detection")]
+            id: deadlock::LockId::new(),
+            #[cfg(feature = "stats")]
+            stats: stats::State::default(),
+            #[cfg(feature = "reader-registry")]
+            readers: Default::default(),
+        }
+    }
+
+    /// The name of the value, which is only stored if a feature that reports
+    /// it is enabled.
+    pub fn name(&self) -> Option<&Cow<'static, str>> {
+        #[cfg(any(feature = "tracing", feature = "hold-warnings"))]
+        return self.name.as_ref();
+        #[cfg(not(any(feature = "tracing", feature = "hold-warnings")))]
+        None
+    }
+}
+
+impl fmt::Debug for LockState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockState").field("name", &self.name()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "deadlock-detection")]
+impl Drop for LockState {
+    fn drop(&mut self) {
+        deadlock::forget(self.id);
+    }
+}
+
+/// Per-handle state, stored in `SharedReadLock` and `WeakReadLock`.
+pub(crate) struct ReaderHandle {
+    #[cfg(feature = "reader-registry")]
+    pub(crate) handle: readers::Handle,
+}
+
+impl ReaderHandle {
+    /// Register a new read handle for the value belonging to `state`.
+    #[track_caller]
+    #[allow(unused_variables)] // unused if no debugging feature is enabled
+    pub(crate) fn new(state: &LockState, weak: bool) -> Self {
+        Self {
+            #[cfg(feature = "reader-registry")]
+            handle: readers::Handle::new(&state.readers, weak, None, Location::caller()),
+        }
+    }
+
+    /// Register a new read handle that is derived from this one, keeping its
+    /// label.
+    #[track_caller]
+    #[allow(unused_variables)] // unused if no debugging feature is enabled
+    pub(crate) fn derive(&self, weak: bool) -> Self {
+        Self {
+            #[cfg(feature = "reader-registry")]
+            handle: self.handle.derive(weak, Location::caller()),
+        }
+    }
+}
+
+/// Per-acquisition state, stored in the lock guards.
+pub(crate) struct Acquisition<'a> {
+    #[cfg(feature = "deadlock-detection")]
+    deadlock: Option<deadlock::Acquisition>,
+    #[cfg(feature = "stats")]
+    stats: Option<stats::Acquisition<'a>>,
+    #[cfg(feature = "tracing")]
+    tracing: Option<Box<instrument::Acquisition>>,
+    #[cfg(feature = "hold-warnings")]
+    hold: Option<Box<HoldTracker>>,
+    #[cfg(feature = "reader-registry")]
+    reader: Option<&'a readers::Handle>,
+    #[cfg(feature = "reader-registry")]
+    reader_acquired: bool,
+    _state: PhantomData<&'a LockState>,
+}
+
+impl<'a> Acquisition<'a> {
+    /// Start tracking an acquisition of the lock belonging to `state`.
+    ///
+    /// Must be called before trying to acquire the lock. `blocking` should be
+    /// `true` if the caller is going to wait for the lock to become available.
+    #[track_caller]
+    #[allow(unused_variables)] // unused if no debugging feature is enabled
+    pub(crate) fn start(state: &'a LockState, kind: LockKind, blocking: bool) -> Self {
+        let location = Location::caller();
+        #[cfg(feature = "async-blocking-check")]
+        if blocking {
+            async_check::check(location);
+        }
+        #[cfg(feature = "test-util")]
+        test_util::delay();
+
+        Self {
+            #[cfg(feature = "deadlock-detection")]
+            deadlock: Some(deadlock::Acquisition::start(state.id, kind, location, blocking)),
+            #[cfg(feature = "stats")]
+            stats: Some(stats::Acquisition::start(&state.stats, kind, blocking)),
+            #[cfg(feature = "tracing")]
+            tracing: Some(Box::new(instrument::Acquisition::start(
+                state.name().map(Cow::as_ref),
+                kind,
+                location,
+                blocking,
+            ))),
+            #[cfg(feature = "hold-warnings")]
+            hold: Some(Box::new(HoldTracker::start(
+                state.name(),
+                kind == LockKind::Write,
+                location,
+            ))),
+            #[cfg(feature = "reader-registry")]
+            reader: None,
+            #[cfg(feature = "reader-registry")]
+            reader_acquired: false,
+            _state: PhantomData,
+        }
+    }
+
+    /// An acquisition that is not tracked, for guards that are created from
+    /// their internal representation.
+    pub(crate) fn untracked() -> Self {
+        Self {
+            #[cfg(feature = "deadlock-detection")]
+            deadlock: None,
+            #[cfg(feature = "stats")]
+            stats: None,
+            #[cfg(feature = "tracing")]
+            tracing: None,
+            #[cfg(feature = "hold-warnings")]
+            hold: None,
+            #[cfg(feature = "reader-registry")]
+            reader: None,
+            #[cfg(feature = "reader-registry")]
+            reader_acquired: false,
+            _state: PhantomData,
+        }
+    }
+
+    /// Associate this acquisition with the read handle it is made through.
+    #[allow(unused_variables, unused_mut)] // unused if no debugging feature is enabled
+    pub(crate) fn for_reader(mut self, reader: &'a ReaderHandle) -> Self {
+        #[cfg(feature = "reader-registry")]
+        {
+            self.reader = Some(&reader.handle);
+        }
+        self
+    }
+
+    /// Wait for the lock to be acquired by calling `f`.
+    pub(crate) fn wait<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &self.tracing {
+            return tracing.wait(f);
+        }
+        f()
+    }
+
+    /// Mark the lock as acquired.
+    pub(crate) fn acquired(&mut self) {
+        #[cfg(feature = "deadlock-detection")]
+        if let Some(deadlock) = &mut self.deadlock {
+            deadlock.acquired();
+        }
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &mut self.stats {
+            stats.acquired();
+        }
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &mut self.tracing {
+            tracing.acquired();
+        }
+        #[cfg(feature = "hold-warnings")]
+        if let Some(hold) = &mut self.hold {
+            hold.acquired();
+        }
+        #[cfg(feature = "reader-registry")]
+        if let Some(reader) = self.reader {
+            reader.acquired();
+            self.reader_acquired = true;
+        }
+    }
+}
+
+impl Drop for Acquisition<'_> {
+    fn drop(&mut self) {
+        #[cfg(feature = "deadlock-detection")]
+        if let Some(deadlock) = &self.deadlock {
+            deadlock.release();
+        }
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.stats {
+            stats.release();
+        }
+        #[cfg(feature = "tracing")]
+        if let Some(tracing) = &self.tracing {
+            tracing.release();
+        }
+        #[cfg(feature = "reader-registry")]
+        if let (Some(reader), true) = (self.reader, self.reader_acquired) {
+            reader.released();
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Traits that abstract over the different flavors of `Shared` and
+//! `SharedReadLock`.
+//!
+//! These allow writing code that is generic over the backend, such as a library
+//! that accepts any kind of read-only handle and leaves the choice between
+//! [`crate::SharedReadLock`], [`crate::lite::SharedReadLock`] and friends up to
+//! its users.
+//!
+//! Implementations for the `readlock-tokio` types live in that crate.
+
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+};
+
+#[cfg(feature = "lite")]
+use crate::lite;
+use crate::{local, Shared, SharedReadGuard, SharedReadLock, SharedWriteGuard};
+
+/// A read-only handle to a shared value that can be locked by blocking the
+/// current thread.
+pub trait ReadLock {
+    /// The type of the value behind the lock.
+    type Target: ?Sized;
+
+    /// The RAII guard returned by [`lock`][Self::lock].
+    type Guard<'a>: Deref<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// Lock the value for reading, blocking the current thread until the
+    /// operation succeeds.
+    fn lock(&self) -> Self::Guard<'_>;
+}
+
+/// A read-only handle to a shared value that can be locked asynchronously.
+pub trait AsyncReadLock {
+    /// The type of the value behind the lock.
+    type Target: ?Sized;
+
+    /// The RAII guard returned by [`lock`][Self::lock].
+    type Guard<'a>: Deref<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// Lock the value for reading, causing the current task to yield until the
+    /// lock has been acquired.
+    fn lock(&self) -> impl Future<Output = Self::Guard<'_>> + Send;
+}
+
+/// The unique owner of a shared value, which can lock it for writing by
+/// blocking the current thread.
+///
+/// Like the inherent methods of the `Shared` types, the methods of this trait
+/// are associated functions, so they don't conflict with methods of the inner
+/// value.
+pub trait WriteOwner: Deref {
+    /// The read-only handle type returned by
+    /// [`get_read_lock`][Self::get_read_lock].
+    type ReadLock: ReadLock<Target = Self::Target>;
+
+    /// The RAII guard returned by [`lock`][Self::lock].
+    type WriteGuard<'a>: DerefMut<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// Lock the value for writing, blocking the current thread until the
+    /// operation succeeds.
+    fn lock(this: &mut Self) -> Self::WriteGuard<'_>;
+
+    /// Get a read-only handle for accessing the same value from elsewhere.
+    fn get_read_lock(this: &Self) -> Self::ReadLock;
+}
+
+/// The unique owner of a shared value, which can lock it for writing
+/// asynchronously.
+///
+/// Like the inherent methods of the `Shared` types, the methods of this trait
+/// are associated functions, so they don't conflict with methods of the inner
+/// value.
+pub trait AsyncWriteOwner: Deref {
+    /// The read-only handle type returned by
+    /// [`get_read_lock`][Self::get_read_lock].
+    type ReadLock: AsyncReadLock<Target = Self::Target>;
+
+    /// The RAII guard returned by [`lock`][Self::lock].
+    type WriteGuard<'a>: DerefMut<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// Lock the value for writing, causing the current task to yield until the
+    /// lock has been acquired.
+    fn lock(this: &mut Self) -> impl Future<Output = Self::WriteGuard<'_>> + Send;
+
+    /// Get a read-only handle for accessing the same value from elsewhere.
+    fn get_read_lock(this: &Self) -> Self::ReadLock;
+}
+
+impl<T: ?Sized> ReadLock for SharedReadLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = SharedReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        SharedReadLock::lock(self)
+    }
+}
+
+impl<T: ?Sized> WriteOwner for Shared<T> {
+    type ReadLock = SharedReadLock<T>;
+    type WriteGuard<'a>
+        = SharedWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(this: &mut Self) -> Self::WriteGuard<'_> {
+        Shared::lock(this)
+    }
+
+    fn get_read_lock(this: &Self) -> Self::ReadLock {
+        Shared::get_read_lock(this)
+    }
+}
+
+#[cfg(feature = "lite")]
+impl<T: ?Sized> ReadLock for lite::SharedReadLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = SharedReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        lite::SharedReadLock::lock(self)
+    }
+}
+
+#[cfg(feature = "lite")]
+impl<T: ?Sized> WriteOwner for lite::Shared<T> {
+    type ReadLock = lite::SharedReadLock<T>;
+    type WriteGuard<'a>
+        = SharedWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(this: &mut Self) -> Self::WriteGuard<'_> {
+        lite::Shared::lock(this)
+    }
+
+    fn get_read_lock(this: &Self) -> Self::ReadLock {
+        lite::Shared::get_read_lock(this)
+    }
+}
+
+impl<T: ?Sized> ReadLock for local::SharedReadLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = local::SharedReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        local::SharedReadLock::lock(self)
+    }
+}
+
+impl<T: ?Sized> WriteOwner for local::Shared<T> {
+    type ReadLock = local::SharedReadLock<T>;
+    type WriteGuard<'a>
+        = local::SharedWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock(this: &mut Self) -> Self::WriteGuard<'_> {
+        local::Shared::lock(this)
+    }
+
+    fn get_read_lock(this: &Self) -> Self::ReadLock {
+        local::Shared::get_read_lock(this)
+    }
+}
@@ -0,0 +1,264 @@
+//! Waiting for a `Shared` to become unique, i.e. to not have any associated
+//! `SharedReadLock`s anymore, and revoking the existing ones.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    task::{Wake, Waker},
+    thread::{self, Thread},
+};
+
+use crate::lock_unpoisoned;
+
+type Hook = Arc<dyn Fn() + Send + Sync>;
+
+/// The number of current `SharedReadLock`s.
+const COUNT: u64 = u32::MAX as u64;
+/// Set while the pinned `SharedReadLock` exists.
+const PINNED: u64 = 1 << 32;
+/// The lowest bit of the generation, which is incremented whenever the
+/// `SharedReadLock`s are revoked.
+const GENERATION: u64 = 1 << 33;
+
+/// The number of `SharedReadLock`s for a value, and who to notify once it drops
+/// to zero. Used by `readlock-tokio`.
+///
+/// The `SharedReadLock`s of `readlock` also record the generation they were
+/// created in, and are revoked by starting a new one. Revoked
+/// `SharedReadLock`s are not counted anymore. In addition, there can be one
+/// pinned `SharedReadLock`, which is not affected by revocations.
+pub struct ReaderCount {
+    /// The count of `SharedReadLock`s of the current generation in the lower
+    /// 32 bits, followed by the `PINNED` bit and the generation.
+    state: AtomicU64,
+    waiters: Mutex<Waiters>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    waker: Option<Waker>,
+    hook: Option<Hook>,
+}
+
+impl ReaderCount {
+    /// Create a new `ReaderCount` without any `SharedReadLock`s.
+    pub fn new() -> Self {
+        Self { state: AtomicU64::new(0), waiters: Mutex::default() }
+    }
+
+    /// Record the creation of a `SharedReadLock`, returning the current
+    /// generation.
+    pub fn acquire(&self) -> u32 {
+        generation(self.state.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Record the creation of a `SharedReadLock` of the given generation.
+    ///
+    /// Returns `false` without doing so if the `SharedReadLock`s of that
+    /// generation have been revoked.
+    pub(crate) fn try_acquire(&self, generation: u32) -> bool {
+        self.update(generation, |state| Some(state + 1)).is_ok()
+    }
+
+    /// Record a `SharedReadLock` being dropped, and notify the waiter and
+    /// hook if it was the last one.
+    pub fn release(&self) {
+        if is_unique(self.state.fetch_sub(1, Ordering::AcqRel) - 1) {
+            self.notify();
+        }
+    }
+
+    /// Like [`release`][Self::release], but doesn't do anything if the
+    /// `SharedReadLock`s of `generation` have been revoked, since they are not
+    /// counted anymore.
+    pub(crate) fn release_from(&self, generation: u32) {
+        if let Ok(state) = self.update(generation, |state| Some(state - 1)) {
+            if is_unique(state - 1) {
+                self.notify();
+            }
+        }
+    }
+
+    /// Record a `SharedReadLock` being turned into the `Shared`, without
+    /// notifying anyone.
+    pub fn release_silently(&self) {
+        self.state.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Turn a `SharedReadLock` of `generation` into the pinned one.
+    ///
+    /// Returns `false` without doing so if the `SharedReadLock`s of that
+    /// generation have been revoked.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is a pinned `SharedReadLock` already.
+    pub(crate) fn pin(&self, generation: u32) -> bool {
+        let result = self.update(generation, |state| {
+            assert!(state & PINNED == 0, "value has a pinned `SharedReadLock` already");
+            Some(state - 1 + PINNED)
+        });
+        result.is_ok()
+    }
+
+    /// Record the pinned `SharedReadLock` being dropped, and notify the waiter
+    /// and hook if it was the last one.
+    pub(crate) fn unpin(&self) {
+        if is_unique(self.state.fetch_sub(PINNED, Ordering::AcqRel) - PINNED) {
+            self.notify();
+        }
+    }
+
+    /// The current generation.
+    pub(crate) fn generation(&self) -> u32 {
+        generation(self.state.load(Ordering::Acquire))
+    }
+
+    /// Revoke all `SharedReadLock`s except for the pinned one by starting a new
+    /// generation.
+    ///
+    /// Must be called while holding the write lock, so revoked
+    /// `SharedReadLock`s can check their generation after acquiring a read
+    /// lock.
+    pub(crate) fn revoke(&self) {
+        let result = self.state.fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+            next_generation(state & !COUNT)
+        });
+        result.expect("too many revocations");
+    }
+
+    /// Start a new generation if there are no `SharedReadLock`s, so none can be
+    /// created anymore.
+    ///
+    /// Returns `false` without doing so if there are any.
+    pub(crate) fn retire(&self) -> bool {
+        let result = self.state.fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+            if is_unique(state) {
+                next_generation(state)
+            } else {
+                None
+            }
+        });
+        result.is_ok()
+    }
+
+    /// The number of `SharedReadLock`s of the current generation, including
+    /// the pinned one.
+    pub(crate) fn count(&self) -> usize {
+        let state = self.state.load(Ordering::Acquire);
+        (state & COUNT) as usize + usize::from(state & PINNED != 0)
+    }
+
+    /// Whether there are no `SharedReadLock`s.
+    ///
+    /// If this returns `true` while the `Shared` can still not be unwrapped,
+    /// a `SharedReadLock` is in the middle of being created or dropped and the
+    /// waiter has to retry without waiting for a notification.
+    pub fn is_zero(&self) -> bool {
+        is_unique(self.state.load(Ordering::Acquire))
+    }
+
+    /// Set the waker to wake once the last `SharedReadLock` is dropped.
+    ///
+    /// Must be called before checking whether the `Shared` is unique, so the
+    /// notification can't be missed.
+    pub fn register(&self, waker: &Waker) {
+        let mut waiters = self.waiters();
+        match &waiters.waker {
+            Some(existing) if existing.will_wake(waker) => {}
+            _ => waiters.waker = Some(waker.clone()),
+        }
+    }
+
+    /// Remove the waker set through [`register`][Self::register].
+    pub fn unregister(&self) {
+        self.waiters().waker = None;
+    }
+
+    /// Set the function that is called whenever the last `SharedReadLock` is
+    /// dropped.
+    pub fn set_hook(&self, hook: impl Fn() + Send + Sync + 'static) {
+        self.waiters().hook = Some(Arc::new(hook));
+    }
+
+    /// Update the state if it is still of the given generation, returning the
+    /// previous state.
+    fn update(&self, generation: u32, f: impl Fn(u64) -> Option<u64>) -> Result<u64, u64> {
+        self.state.fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+            if self::generation(state) == generation {
+                f(state)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn notify(&self) {
+        let (waker, hook) = {
+            let mut waiters = self.waiters();
+            (waiters.waker.take(), waiters.hook.clone())
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        if let Some(hook) = hook {
+            hook();
+        }
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, Waiters> {
+        lock_unpoisoned(&self.waiters)
+    }
+}
+
+impl Default for ReaderCount {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ReaderCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.load(Ordering::Relaxed);
+        f.debug_struct("ReaderCount")
+            .field("count", &(state & COUNT))
+            .field("pinned", &(state & PINNED != 0))
+            .field("generation", &generation(state))
+            .finish_non_exhaustive()
+    }
+}
+
+fn generation(state: u64) -> u32 {
+    (state / GENERATION) as u32
+}
+
+fn next_generation(state: u64) -> Option<u64> {
+    state.checked_add(GENERATION)
+}
+
+/// Whether there are no `SharedReadLock`s, apart from revoked ones.
+fn is_unique(state: u64) -> bool {
+    state & (COUNT | PINNED) == 0
+}
+
+/// A waker that unparks a thread, for waiting in blocking code.
+pub(crate) struct ThreadWaker(Thread);
+
+impl ThreadWaker {
+    pub(crate) fn current() -> Waker {
+        Waker::from(Arc::new(Self(thread::current())))
+    }
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
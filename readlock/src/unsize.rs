@@ -0,0 +1,211 @@
+//! Support for unsized values: conversions from arrays, owned slices and
+//! strings, and unsizing coercions through [`coerce!`][crate::coerce].
+
+use std::{
+    alloc::{self, Layout},
+    mem::{align_of, size_of, ManuallyDrop},
+    ptr,
+    sync::PoisonError,
+};
+
+use crate::{
+    sync::{Arc, RwLock, Weak},
+    track::LockState,
+    Inner, Shared, SharedReadLock, WeakReadLock,
+};
+
+/// Convert a [`Shared`], [`SharedReadLock`] or [`WeakReadLock`] to one with
+/// an unsized inner type, like a trait object or a slice.
+///
+/// This is the equivalent of the implicit unsizing coercions of `Arc` and
+/// other standard library pointer types, which can't be implemented for
+/// custom types on stable Rust.
+///
+/// The macro takes the value to convert and the new inner type, separated by
+/// `=>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Display;
+///
+/// use readlock::{Shared, SharedReadLock};
+///
+/// let mut shared = Shared::new(1);
+/// let readlock: SharedReadLock<dyn Display + Send + Sync> =
+///     readlock::coerce!(Shared::get_read_lock(&shared) => dyn Display + Send + Sync);
+///
+/// *Shared::lock(&mut shared) = 2;
+/// assert_eq!(readlock.lock().to_string(), "2");
+///
+/// let slice = readlock::coerce!(Shared::new([1, 2, 3]) => [u8]);
+/// assert_eq!(slice.len(), 3);
+/// ```
+///
+/// Conversions other than unsizing coercions are rejected:
+///
+/// ```compile_fail
+/// let shared = readlock::coerce!(readlock::Shared::new(1_u8) => u64);
+/// ```
+#[macro_export]
+macro_rules! coerce {
+    ($value:expr => $target:ty) => {{
+        let value = $value;
+        // SAFETY: The closure only performs an unsizing coercion.
+        unsafe { $crate::__private::CoerceUnsized::__coerce_unsized::<$target>(value, |ptr| ptr) }
+    }};
+}
+
+/// Implementation detail of [`coerce!`][crate::coerce].
+#[doc(hidden)]
+pub trait CoerceUnsized<T: ?Sized> {
+    type Output<U: ?Sized>;
+
+    /// # Safety
+    ///
+    /// `f` must return the pointer it is called with, only changing its type
+    /// through an unsizing coercion.
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> Self::Output<U>;
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for Shared<T> {
+    type Output<U: ?Sized> = Shared<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(self, f: impl FnOnce(*const T) -> *const U) -> Shared<U> {
+        // SAFETY: Upheld by the caller.
+        Shared(unsafe { coerce_arc(Shared::into_arc(self), f) })
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for SharedReadLock<T> {
+    type Output<U: ?Sized> = SharedReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> SharedReadLock<U> {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not used again, and its destructor is not run.
+        let (inner, generation, handle) =
+            unsafe { (ptr::read(&this.0), this.1, ptr::read(&this.2)) };
+        // SAFETY: Upheld by the caller.
+        SharedReadLock(unsafe { coerce_arc(inner, f) }, generation, handle)
+    }
+}
+
+impl<T: ?Sized> CoerceUnsized<T> for WeakReadLock<T> {
+    type Output<U: ?Sized> = WeakReadLock<U>;
+
+    unsafe fn __coerce_unsized<U: ?Sized>(
+        self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> WeakReadLock<U> {
+        let WeakReadLock(inner, generation, handle) = self;
+        let ptr = Weak::into_raw(inner);
+        // SAFETY: Upheld by the caller, see `coerce_arc`.
+        let inner = unsafe { Weak::from_raw(f(ptr as *const T) as *const Inner<U>) };
+        WeakReadLock(inner, generation, handle)
+    }
+}
+
+/// # Safety
+///
+/// See [`CoerceUnsized::__coerce_unsized`].
+unsafe fn coerce_arc<T: ?Sized, U: ?Sized>(
+    inner: Arc<Inner<T>>,
+    f: impl FnOnce(*const T) -> *const U,
+) -> Arc<Inner<U>> {
+    // The pointer metadata of `Inner<T>` is the same as that of `T`, so these
+    // casts only change the pointee type. Together with `f`, this is the same
+    // as coercing `Arc<Inner<T>>` to `Arc<Inner<U>>`.
+    let ptr = Arc::into_raw(inner);
+    unsafe { Arc::from_raw(f(ptr as *const T) as *const Inner<U>) }
+}
+
+impl<T> Inner<[T]> {
+    /// Create an `Inner` for the elements of `vec`.
+    ///
+    /// `Arc` can only be constructed for unsized types with a length that is
+    /// known at compile time, through coercion. Here, the length is only known
+    /// at runtime, so the `Inner` is constructed manually in a `Box`.
+    fn from_vec(vec: Vec<T>) -> Box<Self> {
+        let mut header = Inner::new(LockState::new(None), RwLock::new([]));
+        // An `Inner<[T; 0]>` can be coerced to an `Inner<[T]>`, so the fields
+        // of both, including the elements inside of the `RwLock`, are at the
+        // same offsets. The elements of the `Inner<[T]>` just continue after
+        // the offset of the empty array, and its alignment is the same.
+        let data_offset = {
+            let header_addr = &header as *const Inner<[T; 0]> as usize;
+            let data = header.rwlock.get_mut().unwrap_or_else(PoisonError::into_inner);
+            data.as_mut_ptr() as usize - header_addr
+        };
+        let size = size_of::<T>()
+            .checked_mul(vec.len())
+            .and_then(|size| size.checked_add(data_offset))
+            .expect("capacity overflow");
+        // This is `Layout::for_value` of the resulting `Inner<[T]>`.
+        let layout = Layout::from_size_align(size, align_of::<Inner<[T; 0]>>())
+            .expect("capacity overflow")
+            .pad_to_align();
+
+        let len = vec.len();
+        let mut vec = ManuallyDrop::new(vec);
+        // SAFETY: `layout` has a non-zero size since `Inner` has fields other
+        // than the data, and it is at least as large as `Inner<[T; 0]>`. The
+        // header and the elements are written to the parts of the allocation
+        // they occupy in an `Inner<[T]>` of length `len`, in that order since
+        // the padding at the end of the header can overlap the elements.
+        // Ownership of both is moved into the new allocation, and the
+        // allocation is made like `Box` does for this layout.
+        unsafe {
+            let mem = alloc::alloc(layout);
+            if mem.is_null() {
+                alloc::handle_alloc_error(layout);
+            }
+
+            ptr::write(mem as *mut Inner<[T; 0]>, header);
+            ptr::copy_nonoverlapping(vec.as_ptr(), mem.add(data_offset) as *mut T, len);
+            vec.set_len(0);
+            ManuallyDrop::drop(&mut vec);
+
+            Box::from_raw(ptr::slice_from_raw_parts_mut(mem as *mut T, len) as *mut Self)
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for Shared<[T]> {
+    fn from(vec: Vec<T>) -> Self {
+        Self(Arc::from(Inner::from_vec(vec)))
+    }
+}
+
+impl<T> From<Box<[T]>> for Shared<[T]> {
+    fn from(slice: Box<[T]>) -> Self {
+        Self::from(slice.into_vec())
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Shared<[T]> {
+    fn from(array: [T; N]) -> Self {
+        coerce!(Shared::new(array) => [T])
+    }
+}
+
+impl From<String> for Shared<str> {
+    fn from(string: String) -> Self {
+        let inner: Arc<Inner<[u8]>> = Arc::from(Inner::from_vec(string.into_bytes()));
+        let inner = Arc::into_raw(inner);
+        // SAFETY: `str` has the same layout as `[u8]`, and the bytes are valid
+        // UTF-8 since they come from a `String`.
+        Self(unsafe { Arc::from_raw(inner as *const Inner<str>) })
+    }
+}
+
+impl From<Box<str>> for Shared<str> {
+    fn from(string: Box<str>) -> Self {
+        Self::from(String::from(string))
+    }
+}
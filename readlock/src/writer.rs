@@ -0,0 +1,62 @@
+//! Tracking whether the `Shared` for a value is still alive.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Condvar, Mutex,
+};
+
+use crate::{lock_unpoisoned, wait_while_unpoisoned};
+
+/// Set while the `Shared` is alive.
+const ALIVE: usize = 1;
+/// Added for every thread waiting in [`WriterState::wait_dropped`].
+const WAITER: usize = 2;
+
+pub(crate) struct WriterState {
+    /// The `ALIVE` bit, followed by the number of waiting threads. Dropping the
+    /// `Shared` only has to lock `lock` if there are any.
+    state: AtomicUsize,
+    lock: Mutex<()>,
+    dropped: Condvar,
+}
+
+impl WriterState {
+    pub(crate) fn new() -> Self {
+        Self { state: AtomicUsize::new(ALIVE), lock: Mutex::new(()), dropped: Condvar::new() }
+    }
+
+    pub(crate) fn is_alive(&self) -> bool {
+        self.state.load(Ordering::Acquire) & ALIVE != 0
+    }
+
+    /// Block the current thread until [`set_alive(false)`][Self::set_alive]
+    /// is called, or return immediately if it already was.
+    ///
+    /// Also returns once `revoked` returns `true`, which is checked again
+    /// whenever [`notify`][Self::notify] is called.
+    pub(crate) fn wait_dropped(&self, revoked: impl Fn() -> bool) {
+        // Registered before checking, so `set_alive(false)` either sees the
+        // waiter or is seen by it.
+        if self.state.fetch_add(WAITER, Ordering::Acquire) & ALIVE != 0 {
+            let _guard = wait_while_unpoisoned(&self.dropped, lock_unpoisoned(&self.lock), |()| {
+                self.is_alive() && !revoked()
+            });
+        }
+        self.state.fetch_sub(WAITER, Ordering::Relaxed);
+    }
+
+    /// Wake up the threads waiting in [`wait_dropped`][Self::wait_dropped],
+    /// after the result of their `revoked` function changed.
+    pub(crate) fn notify(&self) {
+        let _guard = lock_unpoisoned(&self.lock);
+        self.dropped.notify_all();
+    }
+
+    pub(crate) fn set_alive(&self, value: bool) {
+        if value {
+            self.state.fetch_or(ALIVE, Ordering::Release);
+        } else if self.state.fetch_and(!ALIVE, Ordering::Release) >= WAITER {
+            self.notify();
+        }
+    }
+}
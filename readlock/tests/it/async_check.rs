@@ -0,0 +1,45 @@
+#![cfg(all(feature = "async-blocking-check", not(any(loom, shuttle))))]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use readlock::{async_check, Shared};
+
+fn assert_detected<R>(result: std::thread::Result<R>) {
+    let Err(error) = result else { panic!("blocking acquisition was not detected") };
+    let message = error.downcast::<String>().unwrap();
+    assert!(message.contains("blocking lock acquisition at"), "{message}");
+    assert!(message.contains("inside an async task"), "{message}");
+}
+
+#[tokio::test]
+async fn lock_in_async_task() {
+    let mut shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+
+    // The body of the test runs inside of `block_on`.
+    assert_detected(panic::catch_unwind(AssertUnwindSafe(|| *Shared::lock(&mut shared) += 1)));
+
+    let error = tokio::spawn(async move { *readlock.lock() }).await.unwrap_err();
+    assert_detected::<()>(Err(error.into_panic()));
+
+    let value =
+        tokio::spawn(async move { async_check::allow_blocking(|| *Shared::lock(&mut shared)) })
+            .await
+            .unwrap();
+    assert_eq!(value, 0);
+}
+
+#[test]
+fn lock_in_block_on() {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+
+    // Not inside the runtime.
+    *Shared::lock(&mut shared) += 1;
+
+    runtime.block_on(async {
+        assert_detected(panic::catch_unwind(|| *readlock.lock()));
+        assert_eq!(async_check::allow_blocking(|| *readlock.lock()), 1);
+    });
+}
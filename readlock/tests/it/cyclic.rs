@@ -0,0 +1,26 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use readlock::{Shared, WeakReadLock};
+
+struct Node {
+    value: u8,
+    this: WeakReadLock<Node>,
+}
+
+#[test]
+fn new_cyclic() {
+    let mut shared = Shared::new_cyclic(|weak| {
+        assert!(weak.upgrade().is_none());
+        Node { value: 1, this: weak.clone() }
+    });
+
+    let readlock = shared.this.upgrade().unwrap();
+    Shared::lock(&mut shared).value = 2;
+    assert_eq!(readlock.lock().value, 2);
+    assert!(readlock.lock().this.ptr_eq(&readlock.downgrade()));
+
+    drop(readlock);
+    let weak = shared.this.clone();
+    drop(shared);
+    assert!(weak.is_dead());
+}
@@ -0,0 +1,61 @@
+#![cfg(all(feature = "deadlock-detection", not(any(loom, shuttle))))]
+
+use std::thread;
+
+use readlock::{deadlock, Shared};
+
+#[test]
+#[should_panic = "recursive read lock"]
+fn recursive_read() {
+    let shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let _guard = readlock.lock();
+    let _guard2 = readlock.lock();
+}
+
+#[test]
+fn recursive_try_read() {
+    let shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let _guard = readlock.lock();
+    // Doesn't block, so it can't deadlock.
+    let _guard2 = readlock.try_lock().unwrap();
+}
+
+#[test]
+#[should_panic = "lock order inversion"]
+fn lock_order_inversion() {
+    let a = Shared::new(0);
+    let b = Shared::new(0);
+    let readlock_a = Shared::get_read_lock(&a);
+    let readlock_b = Shared::get_read_lock(&b);
+
+    thread::spawn({
+        let readlock_a = readlock_a.clone();
+        let readlock_b = readlock_b.clone();
+        move || {
+            let _guard_a = readlock_a.lock();
+            let _guard_b = readlock_b.lock();
+        }
+    })
+    .join()
+    .unwrap();
+
+    let _guard_b = readlock_b.lock();
+    let _guard_a = readlock_a.lock();
+}
+
+#[test]
+fn contention_is_not_a_deadlock() {
+    let mut shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let guard = readlock.lock();
+    let join_handle = thread::spawn(move || *Shared::lock(&mut shared) += 1);
+    assert!(deadlock::check().is_empty());
+
+    drop(guard);
+    join_handle.join().unwrap();
+}
@@ -0,0 +1,62 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use readlock::Shared;
+
+#[test]
+fn on_drop() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    Shared::on_drop(&shared, {
+        let calls = calls.clone();
+        let weak = weak.clone();
+        move || {
+            assert!(weak.is_dead());
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    drop(shared);
+    assert!(!weak.is_dead());
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+    drop(readlock);
+    assert!(weak.is_dead());
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn on_drop_unwrap() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let shared = Shared::new(1);
+    Shared::on_drop(&shared, {
+        let calls = calls.clone();
+        move || {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    assert_eq!(Shared::unwrap(shared).unwrap(), 1);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn weak_ptr_eq() {
+    let shared = Shared::new(1);
+    let other = Shared::new(1);
+    let weak = Shared::get_read_lock(&shared).downgrade();
+
+    assert!(weak.ptr_eq(&weak.clone()));
+    assert!(weak.ptr_eq(&Shared::get_read_lock(&shared).downgrade()));
+    assert!(!weak.ptr_eq(&Shared::get_read_lock(&other).downgrade()));
+
+    drop(shared);
+    assert!(weak.is_dead());
+    assert!(weak.ptr_eq(&weak.clone()));
+}
@@ -0,0 +1,45 @@
+#![cfg(all(feature = "hold-warnings", not(any(loom, shuttle))))]
+
+use std::{sync::mpsc, time::Duration};
+
+use readlock::{hold_warnings, Shared};
+
+#[test]
+fn long_hold() {
+    let (tx, rx) = mpsc::channel();
+    hold_warnings::set_threshold(Duration::from_millis(10));
+    hold_warnings::set_handler(move |hold| {
+        if hold.name == Some("hold_warnings::long_hold") {
+            let line = hold.location.line();
+            tx.send((hold.write, line, hold.still_held)).unwrap();
+        }
+    });
+    hold_warnings::spawn_watchdog(Duration::from_millis(5));
+
+    let mut shared = Shared::with_name(0, "hold_warnings::long_hold");
+    let readlock = Shared::get_read_lock(&shared);
+
+    let brief_line = line!() + 1;
+    drop(Shared::lock(&mut shared));
+    // The write guard was held only briefly, so nothing is reported for it
+    // unless the interpreter is slow enough to exceed the threshold.
+    let recv = || loop {
+        let warning = rx.recv().unwrap();
+        if !cfg!(miri) || warning.1 != brief_line {
+            return warning;
+        }
+    };
+
+    // Reported by the watchdog while it is still held, and again on release.
+    let line = line!() + 1;
+    let guard = readlock.lock();
+    assert_eq!(recv(), (false, line, true));
+    drop(guard);
+    assert_eq!(recv(), (false, line, false));
+
+    let line = line!() + 1;
+    let guard = Shared::lock(&mut shared);
+    assert_eq!(recv(), (true, line, true));
+    drop(guard);
+    assert_eq!(recv(), (true, line, false));
+}
@@ -0,0 +1,69 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+};
+
+use readlock::Shared;
+
+#[test]
+fn round_trip() {
+    let mut shared = Shared::new(1);
+    *Shared::lock(&mut shared) = 2;
+
+    let rwlock = Shared::freeze(shared).try_into_inner().unwrap();
+    *rwlock.write().unwrap() += 1;
+
+    let shared = Shared::try_from_inner(rwlock).unwrap();
+    assert_eq!(*shared, 3);
+}
+
+#[test]
+fn try_from_inner_shared() {
+    let rwlock = Arc::new(RwLock::new(1));
+    let weak = Arc::downgrade(&rwlock);
+    let rwlock = Shared::try_from_inner(rwlock).unwrap_err();
+
+    drop(weak);
+    assert!(Shared::try_from_inner(rwlock).is_ok());
+}
+
+#[test]
+fn try_into_inner() {
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    let readlock = readlock.try_into_inner().unwrap_err();
+
+    let weak = readlock.downgrade();
+    drop(shared);
+    let readlock = readlock.try_into_inner().unwrap_err();
+
+    drop(weak);
+    let rwlock = readlock.try_into_inner().unwrap();
+    assert_eq!(*rwlock.read().unwrap(), 1);
+}
+
+#[test]
+fn revoked_try_into_inner() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::revoke_readers(&mut shared);
+    drop(shared);
+
+    assert!(readlock.try_into_inner().is_err());
+}
+
+#[test]
+fn drop_hooks_into_inner() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let shared = Shared::new(1);
+    Shared::on_drop(&shared, {
+        let dropped = Arc::clone(&dropped);
+        move || dropped.store(true, Ordering::SeqCst)
+    });
+
+    let rwlock = Shared::freeze(shared).try_into_inner().unwrap();
+    assert!(dropped.load(Ordering::SeqCst));
+    assert_eq!(*rwlock.read().unwrap(), 1);
+}
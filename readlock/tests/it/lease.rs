@@ -0,0 +1,55 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{sync::Arc, thread};
+
+use readlock::{Shared, WriterLease, WriterSlot};
+
+#[test]
+fn acquire() {
+    let slot = WriterSlot::new(Shared::new(0));
+    let readlock = slot.get_read_lock();
+
+    let mut lease = slot.try_acquire().unwrap();
+    assert!(slot.try_acquire().is_none());
+
+    thread::scope(|s| {
+        let waiter = s.spawn(|| {
+            let mut lease = slot.acquire();
+            *WriterLease::lock(&mut lease) += 1;
+        });
+        assert!(!waiter.is_finished());
+
+        *WriterLease::lock(&mut lease) += 1;
+        drop(lease);
+        waiter.join().unwrap();
+    });
+
+    assert_eq!(*readlock.lock(), 2);
+    assert_eq!(*slot.get_read_lock().lock(), 2);
+
+    let shared = slot.into_inner();
+    assert_eq!(Shared::read_count(&shared), 1);
+    drop(readlock);
+    assert_eq!(Shared::unwrap(shared).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn acquire_async() {
+    let slot = Arc::new(WriterSlot::new(Shared::new(0)));
+    let lease = slot.acquire_async().await;
+
+    let task = tokio::spawn({
+        let slot = slot.clone();
+        async move {
+            let lease = slot.acquire_async().await;
+            assert!(slot.try_acquire().is_none());
+            Shared::read_count(&lease)
+        }
+    });
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+
+    drop(lease);
+    assert_eq!(task.await.unwrap(), 0);
+    assert!(slot.try_acquire().is_some());
+}
@@ -0,0 +1,36 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::thread;
+
+use readlock::leveled::{Shared, ThreadToken};
+
+readlock::lock_levels!(Outer > Middle > Inner);
+
+#[test]
+fn lock_in_order() {
+    let mut outer = Shared::<_, Outer>::new(1);
+    let middle = Shared::<_, Middle>::new(2);
+    let inner = Shared::<_, Inner>::new(3);
+    let middle_reader = Shared::get_read_lock(&middle);
+    let inner_reader = Shared::get_read_lock(&inner);
+
+    let mut thread_token = ThreadToken::get().unwrap();
+    assert!(ThreadToken::get().is_none());
+    let mut token = thread_token.token();
+
+    let (mut outer_guard, mut outer_token) = Shared::lock(&mut outer, &mut token);
+    // Levels can be skipped.
+    let (inner_guard, _) = inner_reader.lock(&mut outer_token);
+    *outer_guard += *inner_guard;
+    drop((outer_guard, inner_guard));
+
+    let (middle_guard, mut middle_token) = middle_reader.lock(&mut token);
+    let (inner_guard, _) = inner_reader.try_lock(&mut middle_token).unwrap();
+    assert_eq!(*middle_guard + *inner_guard, 5);
+    drop((middle_guard, inner_guard));
+    assert_eq!(*outer, 4);
+
+    drop(thread_token);
+    assert!(ThreadToken::get().is_some());
+    thread::spawn(|| assert!(ThreadToken::get().is_some())).join().unwrap();
+}
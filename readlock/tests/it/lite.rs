@@ -1,8 +1,8 @@
-#![cfg(feature = "lite")]
+#![cfg(all(feature = "lite", not(any(loom, shuttle))))]
 
-use std::{thread, time::Duration};
+use std::{fmt::Display, thread, time::Duration};
 
-use readlock::lite::Shared;
+use readlock::lite::{Shared, SharedReadLock};
 
 #[test]
 fn parallel_read_write() {
@@ -23,3 +23,62 @@ fn parallel_read_write() {
         assert!(join_handle.is_finished());
     }
 }
+
+#[test]
+fn upgrade_and_unwrap() {
+    let shared = Shared::new(String::from("foo"));
+    let readlock = Shared::get_read_lock(&shared);
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    let shared = Shared::unwrap(shared).unwrap_err();
+    let readlock = readlock.try_upgrade().unwrap_err();
+    drop(shared);
+
+    let shared = readlock.try_upgrade().unwrap();
+    assert_eq!(Shared::read_count(&shared), 0);
+    assert_eq!(Shared::unwrap(shared).unwrap(), "foo");
+}
+
+#[test]
+fn inner_roundtrip() {
+    let rwlock = Shared::into_inner(Shared::new(5));
+    let readlock = SharedReadLock::from_inner(rwlock.clone());
+    let rwlock = Shared::try_from_inner(rwlock).unwrap_err();
+    drop(rwlock);
+
+    assert_eq!(*readlock.lock(), 5);
+    assert!(readlock.try_into_inner().is_ok());
+}
+
+#[test]
+fn weak_read_lock() {
+    let shared = Shared::new(vec![1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(Shared::weak_count(&shared), 1);
+
+    let readlock = readlock.try_upgrade().unwrap_err();
+    assert_eq!(*weak.upgrade().unwrap().lock(), [1, 2, 3]);
+
+    drop(readlock);
+    assert_eq!(Shared::unwrap(shared).unwrap(), [1, 2, 3]);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn unsized_values() {
+    let mut shared: Shared<[i32]> = Shared::from([1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    Shared::lock(&mut shared)[1] = 5;
+    assert_eq!(*readlock.lock(), [1, 5, 3]);
+    drop((shared, readlock));
+    assert!(weak.upgrade().is_none());
+
+    let mut shared = Shared::new(1);
+    let readlock: SharedReadLock<dyn Display + Send + Sync> =
+        readlock::coerce!(Shared::get_read_lock(&shared) => dyn Display + Send + Sync);
+    *Shared::lock(&mut shared) = 2;
+    assert_eq!(readlock.lock().to_string(), "2");
+    assert_eq!(readlock.downgrade().upgrade().unwrap().lock().to_string(), "2");
+}
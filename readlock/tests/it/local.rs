@@ -0,0 +1,50 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use readlock::local::Shared;
+
+#[test]
+fn read_write() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    for _ in 0..10 {
+        let value: i32 = *shared;
+        *Shared::lock(&mut shared) += value;
+    }
+
+    assert_eq!(*readlock.lock(), 1024);
+}
+
+#[test]
+fn try_lock_while_writing() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let guard = Shared::lock(&mut shared);
+    readlock.try_lock().unwrap_err();
+    drop(guard);
+
+    assert_eq!(*readlock.try_lock().unwrap(), 1);
+}
+
+#[test]
+#[should_panic = "while a `SharedWriteGuard` exists"]
+fn lock_while_writing() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let _guard = Shared::lock(&mut shared);
+    readlock.lock();
+}
+
+#[test]
+fn weak_read_lock() {
+    let shared = Shared::new(String::from("foo"));
+    let weak = Shared::get_read_lock(&shared).downgrade();
+    assert_eq!(Shared::read_count(&shared), 0);
+    assert_eq!(Shared::weak_count(&shared), 1);
+
+    assert_eq!(*weak.upgrade().unwrap().lock(), "foo");
+    assert_eq!(Shared::unwrap(shared).unwrap(), "foo");
+    assert!(weak.upgrade().is_none());
+}
@@ -0,0 +1,41 @@
+#![cfg(loom)]
+
+use loom::thread;
+use readlock::Shared;
+
+#[test]
+fn read_while_writing() {
+    loom::model(|| {
+        let mut shared = Shared::new(0);
+        let readlock = Shared::get_read_lock(&shared);
+
+        let reader = thread::spawn(move || {
+            let value = *readlock.lock();
+            assert!(value == 0 || value == 2, "observed partial write: {value}");
+        });
+
+        {
+            let mut guard = Shared::lock(&mut shared);
+            *guard += 1;
+            *guard += 1;
+        }
+        assert_eq!(*shared, 2);
+
+        reader.join().unwrap();
+    });
+}
+
+#[test]
+fn upgrade_after_writer_dropped() {
+    loom::model(|| {
+        let shared = Shared::new(String::from("value"));
+        let readlock = Shared::get_read_lock(&shared);
+
+        let writer = thread::spawn(move || drop(shared));
+        let _ = readlock.lock().len();
+        writer.join().unwrap();
+
+        let shared = readlock.try_upgrade().unwrap();
+        assert_eq!(Shared::unwrap(shared).unwrap(), "value");
+    });
+}
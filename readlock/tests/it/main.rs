@@ -4,9 +4,37 @@ use std::{self, thread, time::Duration};
 
 use readlock::Shared;
 
+mod async_check;
+mod cyclic;
+mod deadlock;
+mod drop_hooks;
+mod hold_warnings;
+mod inner;
+mod lease;
+mod leveled;
 mod lite;
+mod local;
+mod once;
+mod readers;
+mod revoke;
+mod scoped;
+mod serde;
+mod static_shared;
+mod stats;
+mod test_util;
+mod tracing;
+mod traits;
+mod unique;
+mod unsize;
+mod writer;
+
+// Model checking tests. The other tests use primitives that only work outside
+// of a model, and are disabled when model checking.
+mod loom;
+mod shuttle;
 
 #[test]
+#[cfg_attr(any(loom, shuttle), ignore)]
 fn parallel_read_write() {
     let mut shared = Shared::new(1);
     let readlock = Shared::get_read_lock(&shared);
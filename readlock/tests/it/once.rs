@@ -0,0 +1,82 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{panic, thread};
+
+use readlock::{
+    once::{OnceShared, TryLockError},
+    Shared,
+};
+
+#[test]
+fn lock_waits_for_init() {
+    let once = OnceShared::new();
+    let readlock = once.get_read_lock();
+    let weak = readlock.downgrade();
+    assert!(!readlock.is_initialized());
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::Uninitialized)));
+
+    let reader = thread::spawn({
+        let readlock = readlock.clone();
+        move || *readlock.lock()
+    });
+    assert!(!reader.is_finished());
+
+    let mut shared = once.init(1);
+    assert_eq!(reader.join().unwrap(), 1);
+    assert!(readlock.is_initialized());
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    *Shared::lock(&mut shared) = 2;
+    assert_eq!(*readlock.try_lock().unwrap(), 2);
+    assert_eq!(*weak.upgrade().unwrap().lock(), 2);
+
+    let value_weak = readlock.get().unwrap().downgrade();
+    drop(readlock);
+    assert_eq!(Shared::read_count(&shared), 0);
+    assert_eq!(*weak.upgrade().unwrap().lock(), 2);
+    assert_eq!(Shared::unwrap(shared).unwrap(), 2);
+    assert!(weak.upgrade().is_none());
+    assert!(value_weak.upgrade().is_none());
+}
+
+#[test]
+fn weak_upgrade_after_readers_dropped() {
+    let once = OnceShared::<i32>::new();
+    let weak = once.get_read_lock().downgrade();
+    assert!(weak.upgrade().is_some());
+    drop(once);
+    assert!(weak.upgrade().is_none());
+
+    let once = OnceShared::new();
+    let weak = once.get_read_lock().downgrade();
+    let mut shared = once.init(1);
+    assert_eq!(Shared::read_count(&shared), 0);
+
+    let readlock = weak.upgrade().unwrap();
+    assert_eq!(Shared::read_count(&shared), 1);
+    assert_eq!(*weak.upgrade().unwrap().lock(), 1);
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    Shared::revoke_readers(&mut shared);
+    assert_eq!(*readlock.lock(), 1);
+    drop(readlock);
+    assert_eq!(*weak.upgrade().unwrap().lock(), 1);
+
+    // Revoked while no `SharedReadLock` of the `once` module exists.
+    Shared::revoke_readers(&mut shared);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn dropped_without_init() {
+    let once = OnceShared::<i32>::new();
+    let readlock = once.get_read_lock();
+    let reader = thread::spawn({
+        let readlock = readlock.clone();
+        move || panic::catch_unwind(panic::AssertUnwindSafe(|| *readlock.lock()))
+    });
+
+    drop(once);
+    assert!(reader.join().unwrap().is_err());
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::Uninitialized)));
+}
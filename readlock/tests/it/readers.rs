@@ -0,0 +1,50 @@
+#![cfg(all(feature = "reader-registry", not(any(loom, shuttle))))]
+
+use std::thread;
+
+use readlock::Shared;
+
+#[test]
+fn list_readers() {
+    let shared = Shared::new(0);
+    assert!(Shared::readers(&shared).is_empty());
+
+    let line = line!() + 1;
+    let readlock = Shared::get_read_lock(&shared).with_label("first");
+    let weak = readlock.downgrade();
+    let upgraded = weak.upgrade().unwrap().with_label("upgraded");
+    let readlock2 = thread::scope(|s| {
+        thread::Builder::new()
+            .name("reader-thread".to_owned())
+            .spawn_scoped(s, || Shared::get_read_lock(&shared))
+            .unwrap()
+            .join()
+            .unwrap()
+    });
+
+    let guard = readlock2.lock();
+    let readers = Shared::readers(&shared);
+    assert_eq!(readers.len(), 4);
+
+    assert!(!readers[0].weak);
+    assert_eq!(readers[0].created_at.line(), line);
+    assert_eq!(readers[0].label.as_deref(), Some("first"));
+    assert_eq!(readers[0].thread, thread::current().id());
+    assert!(!readers[0].holding_guard);
+
+    assert!(readers[1].weak);
+    assert_eq!(readers[1].created_at.line(), line + 1);
+    assert_eq!(readers[1].label.as_deref(), Some("first"));
+
+    assert_eq!(readers[2].label.as_deref(), Some("upgraded"));
+
+    assert_eq!(readers[3].thread_name.as_deref(), Some("reader-thread"));
+    assert_eq!(readers[3].label, None);
+    assert!(readers[3].holding_guard);
+
+    drop((guard, readlock, upgraded));
+    let readers = Shared::readers(&shared);
+    assert_eq!(readers.len(), 2);
+    assert!(readers[0].weak);
+    assert!(!readers[1].holding_guard);
+}
@@ -0,0 +1,141 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use readlock::{once::OnceShared, RevokedError, Shared, StaticShared, TryLockError};
+
+#[test]
+fn revoke_readers() {
+    let mut shared = Shared::new(vec![1]);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+
+    Shared::revoke_readers(&mut shared);
+    assert_eq!(*shared, [1]);
+    assert_eq!(Shared::read_count(&shared), 0);
+    assert_eq!(Shared::weak_count(&shared), 1);
+
+    assert_eq!(readlock.lock_checked().unwrap_err(), RevokedError);
+    assert!(!readlock.is_writer_alive());
+    assert_eq!(format!("{readlock:?}"), "<revoked>");
+    assert!(weak.upgrade().is_none());
+    assert!(weak.is_dead());
+    assert!(readlock.downgrade().upgrade().is_none());
+
+    let new_readlock = Shared::get_read_lock(&shared);
+    Shared::lock(&mut shared).push(2);
+    assert_eq!(*new_readlock.lock(), [1, 2]);
+    assert_eq!(format!("{new_readlock:?}"), format!("{shared:?}"));
+    assert!(format!("{shared:?}").contains("[1, 2]"));
+
+    let readlock = readlock.try_upgrade().unwrap_err();
+    drop(new_readlock);
+    assert_eq!(Shared::unwrap(shared).unwrap(), [1, 2]);
+    drop(readlock);
+}
+
+#[test]
+#[should_panic = "`SharedReadLock` was revoked"]
+fn lock_revoked() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::revoke_readers(&mut shared);
+    let _guard = readlock.lock();
+}
+
+#[test]
+fn try_lock_revoked() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::revoke_readers(&mut shared);
+    assert!(matches!(readlock.try_lock_checked(), Err(TryLockError::Revoked)));
+}
+
+#[test]
+fn waits_for_guards() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    let guard = readlock.lock();
+
+    thread::scope(|s| {
+        let revoker = s.spawn(|| Shared::revoke_readers(&mut shared));
+        assert!(!revoker.is_finished());
+        assert_eq!(*guard, 1);
+        drop(guard);
+    });
+
+    assert!(readlock.lock_checked().is_err());
+}
+
+#[test]
+fn hooks_are_kept() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let uniques = Arc::new(AtomicUsize::new(0));
+    let mut shared = Shared::new(1);
+    Shared::on_drop(&shared, {
+        let drops = drops.clone();
+        move || {
+            drops.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    Shared::on_unique(&shared, {
+        let uniques = uniques.clone();
+        move || {
+            uniques.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    let revoked = Shared::get_read_lock(&shared);
+    Shared::revoke_readers(&mut shared);
+    drop(revoked);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(uniques.load(Ordering::Relaxed), 0);
+
+    drop(Shared::get_read_lock(&shared));
+    assert_eq!(uniques.load(Ordering::Relaxed), 1);
+
+    drop(shared);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn static_shared_follows() {
+    let value = StaticShared::new(1);
+    let mut shared = value.take_writer().unwrap();
+    let readlock = value.read_lock().clone();
+
+    Shared::revoke_readers(&mut shared);
+    *Shared::lock(&mut shared) = 2;
+    assert!(readlock.lock_checked().is_err());
+    assert_eq!(*value.read(), 2);
+    assert_eq!(*value.read_lock().lock(), 2);
+
+    Shared::revoke_readers(&mut shared);
+    *Shared::lock(&mut shared) = 3;
+    assert_eq!(*value.read(), 3);
+    assert_eq!(Shared::read_count(&shared), 1);
+}
+
+#[test]
+fn once_shared_follows() {
+    let once = OnceShared::new();
+    let readlock = once.get_read_lock();
+    let mut shared = once.init(1);
+    let revoked = readlock.get().unwrap().clone();
+
+    Shared::revoke_readers(&mut shared);
+    *Shared::lock(&mut shared) = 2;
+    assert!(revoked.lock_checked().is_err());
+    assert_eq!(*readlock.lock(), 2);
+    assert_eq!(*readlock.try_lock().unwrap(), 2);
+
+    drop(readlock);
+    Shared::revoke_readers(&mut shared);
+    assert_eq!(Shared::unwrap(shared).unwrap(), 2);
+}
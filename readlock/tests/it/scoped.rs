@@ -0,0 +1,32 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{sync::TryLockError, thread};
+
+use readlock::scoped::{Shared, Slot};
+
+#[test]
+fn scoped_threads() {
+    let mut slot = Slot::new(vec![1, 2, 3]);
+    let mut shared = Shared::new(&mut slot);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let sums: Vec<i32> = thread::scope(|s| {
+        let readers: Vec<_> =
+            (0..4).map(|_| s.spawn(move || readlock.lock().iter().sum())).collect();
+        readers.into_iter().map(|reader| reader.join().unwrap()).collect()
+    });
+    assert_eq!(sums, [6; 4]);
+
+    Shared::lock(&mut shared).push(4);
+    let guard = readlock.lock();
+    assert_eq!(*guard, [1, 2, 3, 4]);
+    assert_eq!(*shared, [1, 2, 3, 4]);
+    drop(guard);
+
+    let write_guard = Shared::lock(&mut shared);
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::WouldBlock)));
+    drop(write_guard);
+
+    assert_eq!(slot.get_mut().len(), 4);
+    assert_eq!(slot.into_inner(), [1, 2, 3, 4]);
+}
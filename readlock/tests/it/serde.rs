@@ -0,0 +1,66 @@
+#![cfg(all(feature = "serde", not(any(loom, shuttle))))]
+
+use readlock::{local, Shared};
+
+#[test]
+fn roundtrip() {
+    let mut shared: Shared<Vec<u8>> = serde_json::from_str("[1,2,3]").unwrap();
+    Shared::lock(&mut shared).push(4);
+
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(serde_json::to_string(&shared).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::to_string(&readlock).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::to_string(&readlock.lock()).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "[1,2,3,4]");
+
+    drop(readlock);
+    drop(shared);
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
+
+#[test]
+fn revoked() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    Shared::revoke_readers(&mut shared);
+
+    let err = serde_json::to_string(&readlock).unwrap_err();
+    assert_eq!(err.to_string(), "read lock was revoked");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
+
+#[test]
+fn local() {
+    let mut shared: local::Shared<Vec<u8>> = serde_json::from_str("[1,2]").unwrap();
+    let readlock = local::Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(serde_json::to_string(&shared).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&readlock).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "[1,2]");
+
+    let guard = local::Shared::lock(&mut shared);
+    assert!(serde_json::to_string(&readlock).is_err());
+    drop(guard);
+
+    drop((shared, readlock));
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
+
+#[cfg(feature = "lite")]
+#[test]
+fn lite() {
+    use readlock::lite;
+
+    let shared: lite::Shared<Vec<u8>> = serde_json::from_str("[1,2]").unwrap();
+    let readlock = lite::Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    assert_eq!(serde_json::to_string(&shared).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&readlock).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&readlock.lock()).unwrap(), "[1,2]");
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "[1,2]");
+
+    drop((shared, readlock));
+    assert_eq!(serde_json::to_string(&weak).unwrap(), "null");
+}
@@ -0,0 +1,35 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::thread;
+
+use readlock::{Shared, SharedReadGuard, StaticShared};
+
+#[test]
+fn take_writer_once() {
+    static VALUE: StaticShared<u32> = StaticShared::new(1);
+
+    let reader = thread::spawn(|| {
+        let guard: SharedReadGuard<'static, u32> = VALUE.read();
+        *guard
+    });
+    assert_eq!(reader.join().unwrap(), 1);
+
+    let mut writer = VALUE.take_writer().unwrap();
+    assert!(VALUE.take_writer().is_none());
+    *Shared::lock(&mut writer) = 2;
+    assert_eq!(*VALUE.read(), 2);
+
+    drop(writer);
+    assert!(!VALUE.read_lock().is_writer_alive());
+    assert_eq!(VALUE.read_lock().get_frozen(), Some(&2));
+    assert!(VALUE.take_writer().is_none());
+}
+
+#[test]
+fn lazy() {
+    static VALUE: StaticShared<String> = StaticShared::new_lazy(|| "lazy".to_owned());
+
+    assert_eq!(format!("{VALUE:?}"), "<uninitialized>");
+    assert_eq!(*VALUE.read(), "lazy");
+    assert_eq!(format!("{VALUE:?}"), format!("{:?}", VALUE.read_lock()));
+}
@@ -0,0 +1,67 @@
+#![cfg(all(feature = "stats", not(any(loom, shuttle))))]
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use readlock::Shared;
+
+#[test]
+fn acquisitions() {
+    let mut shared = Shared::new(0);
+    let readlock = Shared::get_read_lock(&shared);
+    let _weak = readlock.downgrade();
+    let (locked_tx, locked_rx) = mpsc::channel();
+    let (checked_tx, checked_rx) = mpsc::channel();
+
+    let join_handle = thread::spawn(move || {
+        let read_guard = readlock.lock();
+        locked_tx.send(()).unwrap();
+        checked_rx.recv().unwrap();
+        drop(read_guard);
+        readlock
+    });
+
+    locked_rx.recv().unwrap();
+    let stats = Shared::stats(&shared);
+    assert_eq!(stats.read.acquisitions, 1);
+    assert!(stats.readers_holding);
+    assert!(!stats.writer_waiting);
+    assert_eq!(stats.read_count, 1);
+    assert_eq!(stats.weak_count, 1);
+    checked_tx.send(()).unwrap();
+
+    *Shared::lock(&mut shared) += 1;
+    let readlock = join_handle.join().unwrap();
+    drop(readlock.try_lock().unwrap());
+    drop(readlock);
+
+    let stats = Shared::stats(&shared);
+    assert_eq!(stats.read.acquisitions, 2);
+    assert!(stats.read.max_hold > Duration::ZERO);
+    assert_eq!(stats.write.acquisitions, 1);
+    // Whether the write waited for the reader depends on which thread gets
+    // there first.
+    assert!(stats.write.contended <= 1);
+    assert!(!stats.readers_holding);
+    assert!(!stats.writer_waiting);
+    assert_eq!(stats.read_count, 0);
+}
+
+#[test]
+fn poisoned_write_lock() {
+    let mut shared = Shared::new(0);
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = Shared::lock(&mut shared);
+        panic!("poisoning the lock");
+    }));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        Shared::lock(&mut shared);
+    }));
+    assert!(result.is_err());
+    assert!(!Shared::stats(&shared).writer_waiting);
+}
@@ -0,0 +1,53 @@
+#![cfg(all(feature = "test-util", not(any(loom, shuttle))))]
+
+use std::{sync::TryLockError, time::Duration};
+
+use readlock::{test_util, Shared};
+
+#[test]
+fn poison() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    test_util::poison(&mut shared);
+
+    assert_eq!(*Shared::try_get(&shared).unwrap_err().into_inner(), 1);
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::Poisoned(_))));
+}
+
+#[test]
+fn hold_write_lock() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let held = test_util::hold_write_lock(&mut shared);
+    assert!(matches!(readlock.try_lock(), Err(TryLockError::WouldBlock)));
+    held.release();
+
+    assert_eq!(*readlock.try_lock().unwrap(), 1);
+    *Shared::lock(&mut shared) = 2;
+    assert_eq!(*readlock.lock(), 2);
+}
+
+#[test]
+fn random_delays() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    test_util::set_random_delays(Duration::from_millis(1));
+    *Shared::lock(&mut shared) += 1;
+    assert_eq!(*readlock.lock(), 2);
+    test_util::set_random_delays(Duration::ZERO);
+}
+
+#[test]
+fn defer_writer_drop() {
+    let shared = Shared::new(String::from("value"));
+    let readlock = Shared::get_read_lock(&shared);
+
+    let writer_drop = test_util::defer_writer_drop(shared);
+    let readlock = readlock.try_upgrade().unwrap_err();
+    writer_drop.drop_writer();
+
+    let shared = readlock.try_upgrade().unwrap();
+    assert_eq!(Shared::unwrap(shared).unwrap(), "value");
+}
@@ -0,0 +1,55 @@
+#![cfg(all(feature = "tracing", not(any(loom, shuttle))))]
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use readlock::Shared;
+use tracing_subscriber::{fmt::format::FmtSpan, util::SubscriberInitExt};
+
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn lock_spans() {
+    let output = Output::default();
+    let _subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing_subscriber::filter::LevelFilter::TRACE)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .without_time()
+        .with_writer({
+            let output = output.clone();
+            move || output.clone()
+        })
+        .set_default();
+
+    let mut shared = Shared::with_name(1, "counter");
+    let readlock = Shared::get_read_lock(&shared);
+    *Shared::lock(&mut shared) += 1;
+    drop(readlock.lock());
+
+    assert_eq!(format!("{shared:?}"), format!("counter: {:?}", std::sync::RwLock::new(2)));
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<_> = output.lines().filter(|line| line.contains("close")).collect();
+    assert_eq!(lines.len(), 2, "{output}");
+    for (line, kind) in lines.iter().zip(["write", "read"]) {
+        assert!(line.contains("readlock::lock{"), "{line}");
+        assert!(line.contains(r#"lock.name="counter""#), "{line}");
+        assert!(line.contains(&format!(r#"lock.kind="{kind}""#)), "{line}");
+        assert!(line.contains("tests/it/tracing.rs"), "{line}");
+        assert!(line.contains("wait=") && line.contains("hold="), "{line}");
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use readlock::{
+    local,
+    traits::{ReadLock, WriteOwner},
+    Shared,
+};
+
+fn push_and_read<W: WriteOwner<Target = Vec<u8>>>(mut shared: W) -> usize {
+    let readlock = W::get_read_lock(&shared);
+    W::lock(&mut shared).push(1);
+    len(&readlock)
+}
+
+fn len<R: ReadLock<Target = Vec<u8>>>(readlock: &R) -> usize {
+    readlock.lock().len()
+}
+
+#[test]
+fn generic_over_flavors() {
+    assert_eq!(push_and_read(Shared::new(vec![])), 1);
+    assert_eq!(push_and_read(local::Shared::new(vec![0])), 2);
+    #[cfg(feature = "lite")]
+    assert_eq!(push_and_read(readlock::lite::Shared::new(vec![0, 0])), 3);
+}
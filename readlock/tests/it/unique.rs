@@ -0,0 +1,76 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use readlock::Shared;
+
+#[test]
+fn unwrap_when_unique() {
+    let shared = Shared::new(String::from("value"));
+    let readlock = Shared::get_read_lock(&shared);
+    let weak = readlock.downgrade();
+    let (started_tx, started_rx) = mpsc::channel();
+
+    let join_handle = thread::spawn(move || {
+        started_tx.send(()).unwrap();
+        Shared::unwrap_when_unique(shared)
+    });
+    started_rx.recv().unwrap();
+
+    let readlock2 = weak.upgrade().unwrap();
+    drop(readlock);
+    assert_eq!(*readlock2.lock(), "value");
+    drop(readlock2);
+
+    assert_eq!(join_handle.join().unwrap(), "value");
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn unwrap_when_unique_timeout() {
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+
+    let shared = Shared::unwrap_when_unique_timeout(shared, Duration::from_millis(10)).unwrap_err();
+    assert_eq!(Shared::read_count(&shared), 1);
+
+    drop(readlock);
+    assert_eq!(Shared::unwrap_when_unique_timeout(shared, Duration::from_millis(10)).unwrap(), 1);
+}
+
+#[test]
+fn on_unique() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let shared = Shared::new(1);
+    Shared::on_unique(&shared, {
+        let calls = calls.clone();
+        move || {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    let readlock = Shared::get_read_lock(&shared);
+    let readlock2 = readlock.clone();
+    drop(readlock);
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    drop(readlock2);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    drop(Shared::get_read_lock(&shared));
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+    let readlock = Shared::get_read_lock(&shared);
+    drop(shared);
+    let shared = readlock.try_upgrade().unwrap();
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+    drop(Shared::get_read_lock(&shared));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
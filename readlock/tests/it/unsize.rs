@@ -0,0 +1,95 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use readlock::{Shared, SharedReadLock, WeakReadLock};
+
+#[test]
+fn slices() {
+    let mut shared = Shared::<[u32]>::from(vec![1, 2, 3]);
+    let readlock = Shared::get_read_lock(&shared);
+    Shared::lock(&mut shared)[1] = 5;
+    assert_eq!(*readlock.lock(), [1, 5, 3]);
+
+    let shared = Shared::<[u32]>::from(Vec::new());
+    assert!(shared.is_empty());
+
+    let shared = Shared::<[u8]>::from(Box::from([4, 5]));
+    assert_eq!(*shared, [4, 5]);
+
+    let shared = Shared::<[u16]>::from([6, 7, 8]);
+    assert_eq!(*shared, [6, 7, 8]);
+
+    let readlock: SharedReadLock<[u16]> =
+        readlock::coerce!(Shared::get_read_lock(&Shared::new([6, 7, 8])) => [u16]);
+    assert_eq!(*readlock.lock(), [6, 7, 8]);
+
+    // Element type with a larger alignment than the rest of the allocation.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(align(64))]
+    struct Aligned(u8);
+    let shared = Shared::<[Aligned]>::from(vec![Aligned(1), Aligned(2)]);
+    assert_eq!(*shared, [Aligned(1), Aligned(2)]);
+    assert_eq!(shared.as_ptr() as usize % 64, 0);
+}
+
+#[test]
+fn strings() {
+    let shared = Shared::<str>::from(String::from("hello"));
+    let readlock = Shared::get_read_lock(&shared);
+    assert_eq!(&*readlock.lock(), "hello");
+
+    let shared = Shared::<str>::from(Box::<str>::from(""));
+    assert_eq!(&*shared, "");
+}
+
+#[test]
+fn slice_elements_dropped() {
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let shared = Shared::<[Counted]>::from(vec![
+        Counted(drops.clone()),
+        Counted(drops.clone()),
+        Counted(drops.clone()),
+    ]);
+    let readlock = Shared::get_read_lock(&shared);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+
+    drop(shared);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(readlock);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn trait_objects() {
+    let mut shared = Shared::new(1);
+    let readlock: SharedReadLock<dyn Display + Send + Sync> =
+        readlock::coerce!(Shared::get_read_lock(&shared) => dyn Display + Send + Sync);
+    let weak: WeakReadLock<dyn Display + Send + Sync> =
+        readlock::coerce!(readlock.downgrade() => dyn Display + Send + Sync);
+
+    *Shared::lock(&mut shared) = 2;
+    assert_eq!(readlock.lock().to_string(), "2");
+    assert_eq!(weak.upgrade().unwrap().lock().to_string(), "2");
+
+    let shared: Shared<dyn Display + Send + Sync> =
+        readlock::coerce!(shared => dyn Display + Send + Sync);
+    assert_eq!(shared.to_string(), "2");
+
+    drop(shared);
+    drop(readlock);
+    assert!(weak.upgrade().is_none());
+}
@@ -0,0 +1,64 @@
+#![cfg(not(any(loom, shuttle)))]
+
+use std::{mem, sync::mpsc, thread};
+
+use readlock::Shared;
+
+#[test]
+fn writer_dropped() {
+    let shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    assert!(readlock.is_writer_alive());
+
+    let (waiting_tx, waiting_rx) = mpsc::channel();
+    let join_handle = thread::spawn({
+        let readlock = readlock.clone();
+        move || {
+            waiting_tx.send(()).unwrap();
+            readlock.writer_dropped();
+            assert!(!readlock.is_writer_alive());
+        }
+    });
+
+    waiting_rx.recv().unwrap();
+    drop(shared);
+    join_handle.join().unwrap();
+
+    assert!(!readlock.is_writer_alive());
+    readlock.writer_dropped();
+
+    let shared = readlock.clone().try_upgrade().unwrap_err();
+    drop(readlock);
+    let shared = shared.try_upgrade().unwrap();
+    assert!(Shared::get_read_lock(&shared).is_writer_alive());
+}
+
+#[test]
+fn freeze() {
+    let mut shared = Shared::new(String::from("initial"));
+    let weak = Shared::get_read_lock(&shared).downgrade();
+    assert_eq!(Shared::get_read_lock(&shared).get_frozen(), None);
+
+    *Shared::lock(&mut shared) = String::from("frozen");
+    let readlock = Shared::freeze(shared);
+    assert!(!readlock.is_writer_alive());
+    assert_eq!(readlock.get_frozen().unwrap(), "frozen");
+    assert_eq!(weak.upgrade().unwrap().get_frozen().unwrap(), "frozen");
+
+    drop(weak);
+    let mut shared = readlock.try_upgrade().unwrap();
+    *Shared::lock(&mut shared) = String::from("thawed");
+    assert_eq!(Shared::get_read_lock(&shared).get_frozen(), None);
+}
+
+#[test]
+fn drop_with_leaked_write_guard() {
+    let mut shared = Shared::new(1);
+    let readlock = Shared::get_read_lock(&shared);
+    mem::forget(Shared::lock(&mut shared));
+
+    drop(shared);
+    assert!(!readlock.is_writer_alive());
+    assert_eq!(readlock.get_frozen(), None);
+    assert!(readlock.try_lock().is_err());
+}